@@ -0,0 +1,98 @@
+use std::f32::consts::FRAC_PI_2;
+use bevy::prelude::*;
+use crate::events::PlayerBounced;
+use crate::player::*;
+use crate::projectiles::Shooter;
+use crate::skins::PlayerSkin;
+
+pub const SQUASH_SECS: f32 = 0.06;
+pub const STRETCH_SECS: f32 = 0.25;
+pub const SQUASH_SCALE: Vec2 = Vec2::new(1.3, 0.7);
+pub const STRETCH_SCALE: Vec2 = Vec2::new(0.85, 1.15);
+// Швидкість, з якою масштаб повертається до звичайного після перерваної анімації
+pub const SCALE_RECOVERY: f32 = 12.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlayerAnimState {
+    #[default]
+    Falling,
+    Launch,
+    Shooting,
+}
+
+#[derive(Component, Default)]
+pub struct PlayerAnimation {
+    pub state: PlayerAnimState,
+    pub elapsed: f32,
+}
+
+impl PlayerAnimation {
+    fn set_state(&mut self, state: PlayerAnimState) {
+        if self.state != state {
+            self.state = state;
+            self.elapsed = 0.0;
+        }
+    }
+}
+
+fn smoothstep(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+// Приземлення: сплющення, потім витягування вгору і плавне повернення до звичайної форми
+fn launch_scale(elapsed: f32) -> Vec2 {
+    if elapsed < SQUASH_SECS {
+        SQUASH_SCALE.lerp(STRETCH_SCALE, smoothstep(elapsed / SQUASH_SECS))
+    } else {
+        STRETCH_SCALE.lerp(Vec2::ONE, smoothstep((elapsed - SQUASH_SECS) / (STRETCH_SECS - SQUASH_SECS)))
+    }
+}
+
+pub fn animate_player(
+    time: Res<Time>,
+    mut bounced: EventReader<PlayerBounced>,
+    mut player_q: Query<(Entity, &Children, &mut PlayerAnimation, &Shooter, &PlayerSkin), With<Player>>,
+    mut body_q: Query<(&mut Transform, &mut Sprite), With<PlayerBody>>,
+) {
+    let dt = time.delta_secs();
+    let bounced: Vec<Entity> = bounced.read().map(|event| event.player).collect();
+
+    for (entity, children, mut animation, shooter, skin) in player_q.iter_mut() {
+        let mut bodies = body_q.iter_many_mut(children);
+        let Some((mut t, mut sprite)) = bodies.fetch_next() else { continue };
+        let launched = bounced.contains(&entity);
+
+        if !shooter.pose.finished() {
+            animation.set_state(PlayerAnimState::Shooting);
+        } else if launched {
+            animation.set_state(PlayerAnimState::Launch);
+            animation.elapsed = 0.0;
+        } else if animation.state != PlayerAnimState::Launch || animation.elapsed >= STRETCH_SECS {
+            animation.set_state(PlayerAnimState::Falling);
+        }
+        animation.elapsed += dt;
+
+        let scale = match animation.state {
+            PlayerAnimState::Launch => launch_scale(animation.elapsed),
+            _ => t.scale.truncate().lerp(Vec2::ONE, (SCALE_RECOVERY * dt).min(1.0)),
+        };
+        t.scale = scale.extend(1.0);
+
+        // Під час пострілу дудл розвертається носом догори
+        let facing_left = sprite.flip_x != skin.faces_left;
+        t.rotation = match animation.state {
+            PlayerAnimState::Shooting if facing_left => Quat::from_rotation_z(-FRAC_PI_2),
+            PlayerAnimState::Shooting => Quat::from_rotation_z(FRAC_PI_2),
+            _ => Quat::IDENTITY,
+        };
+
+        if let (Some(frames), Some(atlas)) = (&skin.atlas, &mut sprite.texture_atlas) {
+            atlas.index = match animation.state {
+                PlayerAnimState::Falling => frames.fall,
+                PlayerAnimState::Launch => frames.launch,
+                PlayerAnimState::Shooting => frames.shoot,
+            };
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use bevy::prelude::*;
+use crate::player::*;
+use crate::projectiles::Projectile;
+
+// Прямокутник зіткнень з центром у Transform сутності, розміром зі спрайт
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Collider {
+    pub half_size: Vec2,
+}
+
+impl Collider {
+    pub fn new(half_size: Vec2) -> Self {
+        Self { half_size }
+    }
+
+    pub fn from_size(size: Vec2) -> Self {
+        Self { half_size: size / 2.0 }
+    }
+
+    pub fn overlaps_x(&self, position: Vec2, other: &Collider, other_position: Vec2) -> bool {
+        (position.x - other_position.x).abs() < self.half_size.x + other.half_size.x
+    }
+
+    pub fn overlaps(&self, position: Vec2, other: &Collider, other_position: Vec2) -> bool {
+        self.overlaps_x(position, other, other_position)
+            && (position.y - other_position.y).abs() < self.half_size.y + other.half_size.y
+    }
+}
+
+// На цю поверхню можна приземлитися зверху: платформи, пружини, монстри
+#[derive(Component)]
+pub struct Landable;
+
+// Поверхня, що ламається під гравцем: Landed надходить, але гравець летить далі,
+// тож вона не заступає тверду поверхню під собою
+#[derive(Component)]
+pub struct PassThrough;
+
+// Верхній край поверхні у світі на початку поточного кроку, до руху платформ і їхнього вирівнювання
+#[derive(Component, Clone, Copy, Debug)]
+pub struct PreviousTop {
+    pub y: f32,
+}
+
+impl Default for PreviousTop {
+    // Ще не запам'ятований край: на першому кроці приземлитися не вийде
+    fn default() -> Self {
+        Self { y: f32::INFINITY }
+    }
+}
+
+// Гравець упав на поверхню згори між двома кроками
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Landed {
+    pub player: Entity,
+    pub surface: Entity,
+}
+
+// Гравець перетнувся з предметом чи ворогом, але не приземлився на нього
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Touched {
+    pub player: Entity,
+    pub other: Entity,
+}
+
+// Висота ніг гравця на початку поточного кроку, до руху та зіткнень
+#[derive(Component, Clone, Copy, Debug)]
+pub struct PreviousFeet {
+    pub y: f32,
+}
+
+pub fn remember_feet_position(mut player_q: Query<(&Transform, &Collider, &mut PreviousFeet), With<Player>>) {
+    for (t, body, mut previous) in player_q.iter_mut() {
+        previous.y = t.translation.y - body.half_size.y;
+    }
+}
+
+// Предмети на платформах рухаються разом з нею. GlobalTransform оновлюється лише в PostUpdate
+// і для щойно створених предметів ще порожній, тому у фіксованому кроці світове положення
+// складається з положення платформи та власного зміщення предмета.
+fn world_position(transform: &Transform, parent: Option<&Parent>, parent_q: &Query<&Transform>) -> Vec2 {
+    let local = transform.translation.truncate();
+    match parent.and_then(|parent| parent_q.get(parent.get()).ok()) {
+        Some(parent_transform) => local + parent_transform.translation.truncate(),
+        None => local,
+    }
+}
+
+pub fn remember_surface_tops(
+    mut surface_q: Query<(&Transform, &Collider, Option<&Parent>, &mut PreviousTop)>,
+    parent_q: Query<&Transform>,
+) {
+    for (transform, collider, parent, mut previous) in surface_q.iter_mut() {
+        previous.y = world_position(transform, parent, &parent_q).y + collider.half_size.y;
+    }
+}
+
+// Ноги опинилися нижче верхнього краю, хоча на попередньому кроці були над ним
+pub fn crossed_top(previous_feet: f32, feet: f32, previous_top: f32, top: f32) -> bool {
+    previous_feet >= previous_top && feet <= top
+}
+
+#[allow(clippy::type_complexity)]
+pub fn detect_player_collisions(
+    player_q: Query<(Entity, &Transform, &Collider, &VelocityInY, &PreviousFeet), With<Player>>,
+    collider_q: Query<(Entity, &Transform, &Collider, Option<&Parent>, Option<&PreviousTop>, Has<Landable>,
+        Has<PassThrough>), (Without<Player>, Without<Projectile>)>,
+    parent_q: Query<&Transform>,
+    mut landed: EventWriter<Landed>,
+    mut touched: EventWriter<Touched>,
+) {
+    for (player_entity, player, body, vel_y, previous) in player_q.iter() {
+        let position = player.translation.truncate();
+        let feet = position.y - body.half_size.y;
+        // Ноги могли пролетіти крізь платформу за один крок, тож перевіряється весь відрізок їхнього руху
+        let previous_feet = previous.y;
+
+        let mut best_landing: Option<(Entity, f32)> = None;
+        let mut passed_through = Vec::new();
+        let mut overlapping = Vec::new();
+
+        for (entity, transform, collider, parent, previous_top, landable, pass_through) in collider_q.iter() {
+            let other_position = world_position(transform, parent, &parent_q);
+
+            if !body.overlaps_x(position, collider, other_position) {
+                continue;
+            }
+
+            let top = other_position.y + collider.half_size.y;
+            let previous_top = previous_top.map_or(top, |previous| previous.y);
+
+            if landable && vel_y.y <= 0.0 && crossed_top(previous_feet, feet, previous_top, top) {
+                if pass_through {
+                    passed_through.push(entity);
+                } else if best_landing.is_none_or(|(_, best_top)| top > best_top) {
+                    // З кількох твердих поверхонь за один крок гравець стає на найвищу
+                    best_landing = Some((entity, top));
+                }
+            } else if body.overlaps(position, collider, other_position) {
+                overlapping.push(entity);
+            }
+        }
+
+        for surface in passed_through {
+            landed.send(Landed { player: player_entity, surface });
+        }
+        if let Some((surface, _)) = best_landing {
+            landed.send(Landed { player: player_entity, surface });
+        }
+        for other in overlapping {
+            touched.send(Touched { player: player_entity, other });
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+pub const DIFFICULTY_MANIFEST: &str = "difficulty.ron";
+
+// Висота, з якої починає діяти етап, і параметри генерації на ній.
+// Між етапами значення інтерполюються лінійно, після останнього лишаються сталими.
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct DifficultyStage {
+    pub altitude: f32,
+    pub min_gap: f32,
+    pub max_gap: f32,
+    pub brown_chance: f64,
+    pub moving_chance: f64,
+    pub vanishing_chance: f64,
+    pub exploding_chance: f64,
+    pub monster_chance: f64,
+    pub black_hole_chance: f64,
+}
+
+// Та сама крива, вбудована в гру на випадок, коли assets/difficulty.ron відсутній або зіпсований
+const BUILTIN_CURVE: &str = include_str!("../assets/difficulty.ron");
+
+impl DifficultyStage {
+    fn validate(&self) -> Result<(), String> {
+        if !(self.min_gap > 0.0 && self.max_gap > self.min_gap) {
+            return Err(format!("на висоті {} потрібно 0 < min_gap < max_gap", self.altitude));
+        }
+
+        let chances = [
+            self.brown_chance,
+            self.moving_chance,
+            self.vanishing_chance,
+            self.exploding_chance,
+            self.monster_chance,
+            self.black_hole_chance,
+        ];
+        if chances.iter().any(|chance| !(0.0..=1.0).contains(chance)) {
+            return Err(format!("на висоті {} імовірності мають бути від 0 до 1", self.altitude));
+        }
+
+        // Різновид платформи обирається одним кидком, тож їхні шанси ділять між собою одиницю
+        let platform_chances = self.brown_chance + self.moving_chance + self.vanishing_chance + self.exploding_chance;
+        if platform_chances > 1.0 {
+            return Err(format!("на висоті {} шанси різновидів платформ разом перевищують 1", self.altitude));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Resource)]
+pub struct DifficultyCurve {
+    pub stages: Vec<DifficultyStage>,
+}
+
+impl Default for DifficultyCurve {
+    fn default() -> Self {
+        let stages = Self::parse(BUILTIN_CURVE).expect("вбудована крива складності некоректна");
+        Self { stages }
+    }
+}
+
+impl DifficultyCurve {
+    fn parse(content: &str) -> Result<Vec<DifficultyStage>, String> {
+        let stages = ron::from_str::<Vec<DifficultyStage>>(content).map_err(|error| error.to_string())?;
+
+        if stages.is_empty() {
+            return Err("крива не має жодного етапу".to_string());
+        }
+        // Інтерполяція ділить на різницю висот, тож етапи мають строго зростати
+        if stages.windows(2).any(|pair| pair[0].altitude >= pair[1].altitude) {
+            return Err("етапи мають іти за зростанням висоти".to_string());
+        }
+        for stage in &stages {
+            stage.validate()?;
+        }
+        Ok(stages)
+    }
+
+    pub fn load() -> Self {
+        let path = FileAssetReader::get_base_path().join("assets").join(DIFFICULTY_MANIFEST);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(error) => {
+                warn!("Не вдалося відкрити криву складності {:?}: {}", path, error);
+                return Self::default();
+            }
+        };
+
+        match Self::parse(&content) {
+            Ok(stages) => Self { stages },
+            Err(error) => {
+                warn!("Не вдалося прочитати криву складності {:?}: {}", path, error);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn at(&self, altitude: f32) -> DifficultyStage {
+        let first = self.stages[0];
+        if altitude <= first.altitude {
+            return first;
+        }
+
+        for pair in self.stages.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            if altitude < to.altitude {
+                let k = (altitude - from.altitude) / (to.altitude - from.altitude);
+                let lerp = |a: f64, b: f64| a + (b - a) * k as f64;
+
+                return DifficultyStage {
+                    altitude,
+                    min_gap: from.min_gap + (to.min_gap - from.min_gap) * k,
+                    max_gap: from.max_gap + (to.max_gap - from.max_gap) * k,
+                    brown_chance: lerp(from.brown_chance, to.brown_chance),
+                    moving_chance: lerp(from.moving_chance, to.moving_chance),
+                    vanishing_chance: lerp(from.vanishing_chance, to.vanishing_chance),
+                    exploding_chance: lerp(from.exploding_chance, to.exploding_chance),
+                    monster_chance: lerp(from.monster_chance, to.monster_chance),
+                    black_hole_chance: lerp(from.black_hole_chance, to.black_hole_chance),
+                };
+            }
+        }
+
+        *self.stages.last().unwrap_or(&first)
+    }
+}
@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+use crate::game::{GameOverReason, Score};
+
+// Ігрові події: системи фізики лише повідомляють про те, що сталося,
+// а звук, рахунок, інтерфейс та частинки реагують на ці повідомлення самостійно.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BounceSurface {
+    Platform,
+    PowerUp,
+    Monster,
+}
+
+// Гравець відштовхнувся від платформи, пружини чи монстра. Різновид поверхні передається
+// в події, бо зникаюча платформа чи розчавлений монстр на цей момент уже видалені.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PlayerBounced {
+    pub player: Entity,
+    pub surface_kind: BounceSurface,
+}
+
+// Монстра розчавлено або збито снарядом
+#[derive(Event, Clone, Copy, Debug)]
+pub struct MonsterKilled;
+
+// Гравець стрибнув на пружину чи батут або підібрав предмет для польоту
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PowerUpCollected;
+
+// Платформа зламалася або вибухнула
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PlatformBroken {
+    pub position: Vec3,
+    pub color: Color,
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct GameOver {
+    pub reason: GameOverReason,
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ScoreChanged {
+    pub score: Score,
+}
+
+pub struct GameEventsPlugin;
+
+impl Plugin for GameEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PlayerBounced>()
+            .add_event::<MonsterKilled>()
+            .add_event::<PowerUpCollected>()
+            .add_event::<PlatformBroken>()
+            .add_event::<GameOver>()
+            .add_event::<ScoreChanged>();
+    }
+}
@@ -1,392 +1,640 @@
-use bevy::prelude::*;
-use crate::camera::MainCamera;
-use crate::player::*;
-use crate::platforms::{Platform, BrownPlatform};
-
-#[derive(Component)]
-pub struct Background {
-    pub index: i16,
-}
-
-#[derive(Component)]
-pub struct GameOverStruct;
-
-#[derive(Component)]
-pub struct PauseText;
-
-#[derive(Component)]
-pub struct StartText;
-
-#[derive(Resource)]
-pub struct Paused(pub bool);
-
-#[derive(Resource, Default)]
-pub struct CountStruct {
-    pub count: u16,
-}
-
-#[derive(Component)]
-pub struct CountText;
-
-#[derive(States, Debug, Clone, Eq, PartialEq, Hash, Default)]
-pub enum GameState {
-    #[default]
-    NotStarted,
-    InProcessGame,
-    GameOver,
-}
-
-pub fn background_for_game(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let texture = asset_server.load("images/backgrounds/background.png");
-
-    for i in -1..=1 {
-        commands.spawn((
-            Sprite {
-                image: texture.clone(),
-                ..default()
-            },
-            Transform::from_xyz(0.0, i as f32 * 850.0, -1.0),
-            Background { index: i },
-        ));
-    }
-}
-
-pub fn background_follow_camera(
-    camera_q: Query<&Transform, (With<MainCamera>, Without<Background>, Without<Player>)>,
-    mut background_q: Query<(&mut Transform, &mut Background), (Without<MainCamera>, Without<Player>)>,
-) {
-    let Ok(camera_transform) = camera_q.get_single() else { return };
-    let camera_y = camera_transform.translation.y;
-
-    for (mut background_transform, mut background) in background_q.iter_mut() {
-        let background_y = background_transform.translation.y;
-        if camera_y - background_y > 850.0 {
-            background_transform.translation.y += 3.0 * 850.0;
-            background.index += 3;
-        } else if background_y - camera_y > 850.0 {
-            background_transform.translation.y -= 3.0 * 850.0;
-            background.index -= 3;
-        }
-    }
-}
-
-pub fn pause(
-    mut paused: ResMut<Paused>,
-    key_code: Res<ButtonInput<KeyCode>>,
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    pause_query: Query<Entity, With<PauseText>>
-) {
-    if key_code.just_pressed(KeyCode::KeyP) {
-        paused.0 = !paused.0;
-
-
-        if paused.0 {
-            commands.spawn((
-                Node {
-                    position_type: PositionType::Absolute,
-                    width: Val::Percent(100.0),
-                    height: Val::Percent(100.0),
-                    justify_content: JustifyContent::Center,
-                    align_items: AlignItems::Center,
-                    ..default()
-                },
-                PauseText
-            )).with_children(|parent| {
-                parent.spawn((
-                    Text::new("Пауза"),
-                    TextFont {
-                        font: asset_server.load("fonts/Arsenal-Regular.ttf"),
-                        font_size: 60.0,
-                        ..default()
-                    },
-                    TextColor(Color::BLACK),
-                ));
-            });
-            commands.spawn((
-                AudioPlayer::new(asset_server.load("music/pause.mp3")),
-                PlaybackSettings::ONCE,
-            ));
-        } else {
-            for entity in pause_query.iter() {
-                commands.entity(entity).despawn_recursive();
-            }
-        }
-    }
-}
-
-pub fn game_over(
-    mut next_state: ResMut<NextState<GameState>>,
-    query: Query<&Transform, With<Player>>,
-    state: Res<State<GameState>>,
-    camera_query: Query<&Transform, (With<MainCamera>, Without<Player>)>,
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-) {
-    if *state.get() == GameState::InProcessGame {
-        if let Ok(player_transform) = query.get_single() {
-            if let Ok(camera_transform) = camera_query.get_single() {
-                if player_transform.translation.y < camera_transform.translation.y - 500.0 {
-                    next_state.set(GameState::GameOver);
-                    commands.spawn((
-                        AudioPlayer::new(asset_server.load("music/game_over.mp3")),
-                        PlaybackSettings::ONCE,
-                    ));
-                }
-            }
-        }
-    }
-}
-
-pub fn show_game_over(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    state: Res<State<GameState>>,
-    game_over_query: Query<Entity, With<GameOverStruct>>,
-    count: Res<CountStruct>,
-) {
-    if *state.get() == GameState::GameOver && game_over_query.is_empty() {
-        let high_score = std::fs::read_to_string("high_score.txt")
-            .unwrap_or_else(|_| "0".to_string())
-            .parse::<u16>()
-            .unwrap_or(0);
-
-        let is_new_record = count.count > high_score;
-
-        if is_new_record {
-            let _ = std::fs::write("high_score.txt", count.count.to_string());
-        }
-
-        commands.spawn((
-            Node {
-                position_type: PositionType::Absolute,
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                flex_direction: FlexDirection::Column,
-                row_gap: Val::Px(20.0),
-                ..default()
-            },
-            GameOverStruct
-        )).with_children(|parent| {
-            parent.spawn((
-                Text::new("Гра програна!"),
-                TextFont {
-                    font: asset_server.load("fonts/Arsenal-Regular.ttf"),
-                    font_size: 50.0,
-                    ..default()
-                },
-                TextColor(Color::BLACK),
-            ));
-
-            // Поточний рахунок
-            parent.spawn((
-                Text::new(format!("Ваш рахунок: {}", count.count)),
-                TextFont {
-                    font: asset_server.load("fonts/Mariupol-Bold.ttf"),
-                    font_size: 40.0,
-                    ..default()
-                },
-                TextColor(Color::BLACK),
-            ));
-
-            if is_new_record {
-                parent.spawn((
-                    Text::new("Новий рекорд!"),
-                    TextFont {
-                        font: asset_server.load("fonts/Mariupol-Medium.ttf"),
-                        font_size: 45.0,
-                        ..default()
-                    },
-                    TextColor(Color::BLACK),
-                ));
-            } else {
-                parent.spawn((
-                    Text::new(format!("Рекорд: {}", high_score)),
-                    TextFont {
-                        font: asset_server.load("fonts/Mariupol-Regular.ttf"),
-                        font_size: 35.0,
-                        ..default()
-                    },
-                    TextColor(Color::BLACK),
-                ));
-            }
-
-            parent.spawn((
-                Text::new("Натисніть R для перезапуску"),
-                TextFont {
-                    font: asset_server.load("fonts/times.ttf"),
-                    font_size: 30.0,
-                    ..default()
-                },
-                TextColor(Color::BLACK),
-            ));
-        });
-    }
-}
-
-pub fn show_start_screen(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    state: Res<State<GameState>>,
-    start_query: Query<Entity, With<StartText>>
-) {
-    if *state.get() == GameState::NotStarted && start_query.is_empty() {
-        commands.spawn((
-            Node {
-                position_type: PositionType::Absolute,
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                ..default()
-            },
-            StartText
-        )).with_children(|parent| {
-            parent.spawn((
-                Text::new("Натисніть M для початку гри"),
-                TextFont {
-                    font: asset_server.load("fonts/Arsenal-Regular.ttf"),
-                    font_size: 50.0,
-                    ..default()
-                },
-                TextColor(Color::BLACK),
-            ));
-        });
-    }
-}
-
-pub fn clean_start_screen(
-    mut commands: Commands,
-    start_query: Query<Entity, With<StartText>>,
-) {
-    for entity in start_query.iter() {
-        commands.entity(entity).despawn_recursive();
-    }
-}
-
-pub fn clean_on_restart(
-    mut commands: Commands,
-    player_query: Query<Entity, With<Player>>,
-    platform_query: Query<Entity, With<Platform>>,
-    brown_platform_query: Query<Entity, With<BrownPlatform>>,
-    game_over_query: Query<Entity, With<GameOverStruct>>,
-    start_query: Query<Entity, With<StartText>>,
-    pause_query: Query<Entity, With<PauseText>>,
-    mut camera_query: Query<&mut Transform, (With<MainCamera>, Without<Background>)>,
-    mut background_query: Query<(&mut Transform, &Background), (With<Background>, Without<MainCamera>)>,
-    mut count: ResMut<CountStruct>,
-) {
-    for entity in player_query.iter() {
-        let _ = commands.get_entity(entity).map(|mut entity_commands|
-            entity_commands.try_despawn()
-        );
-    }
-
-    for entity in platform_query.iter() {
-        let _ = commands.get_entity(entity).map(|mut entity_commands|
-            entity_commands.try_despawn()
-        );
-    }
-
-    for entity in brown_platform_query.iter() {
-        let _ = commands.get_entity(entity).map(|mut entity_commands|
-            entity_commands.try_despawn()
-        );
-    }
-
-    for entity in game_over_query.iter() {
-        let _ = commands.get_entity(entity).map(|entity_commands|
-            entity_commands.try_despawn_recursive()
-        );
-    }
-
-    for entity in start_query.iter() {
-        let _ = commands.get_entity(entity).map(|entity_commands|
-            entity_commands.try_despawn_recursive()
-        );
-    }
-
-    for entity in pause_query.iter() {
-        let _ = commands.get_entity(entity).map(|entity_commands|
-            entity_commands.try_despawn_recursive()
-        );
-    }
-
-    if let Ok(mut camera_transform) = camera_query.get_single_mut() {
-        camera_transform.translation.y = 0.0;
-    }
-
-    for (mut bg_transform, bg) in background_query.iter_mut() {
-        bg_transform.translation.y = bg.index as f32 * 850.0;
-    }
-
-    count.count = 0;
-}
-
-pub fn restart(
-    mut next_state: ResMut<NextState<GameState>>,
-    key_code: Res<ButtonInput<KeyCode>>,
-    state: Res<State<GameState>>
-) {
-    if key_code.just_pressed(KeyCode::KeyR) && *state.get() == GameState::GameOver {
-        next_state.set(GameState::NotStarted);
-    }
-}
-
-pub fn start_game(
-    mut next_state: ResMut<NextState<GameState>>,
-    key_code: Res<ButtonInput<KeyCode>>,
-    state: Res<State<GameState>>
-) {
-    if key_code.just_pressed(KeyCode::KeyM) && *state.get() == GameState::NotStarted {
-        next_state.set(GameState::InProcessGame);
-    }
-}
-
-pub fn not_paused(paused: Res<Paused>) -> bool {
-    !paused.0
-}
-
-pub fn setup_count(mut commands: Commands, count: Res<CountStruct>,
-asset_server: Res<AssetServer>) {
-    commands.spawn((
-        CountText,
-        Text::new(format!("Рахунок: {}", count.count)),
-        TextFont {
-            font: asset_server.load("fonts/Arsenal-Regular.ttf"),
-            font_size: 30.0,
-            ..default()
-        },
-        TextColor(Color::BLACK),
-        Node {
-            position_type: PositionType::Absolute,
-            top: Val::Px(10.0),
-            left: Val::Px(10.0),
-            ..default()
-        }
-    ));
-}
-
-pub fn update_count(
-    mut count: ResMut<CountStruct>,
-    player_q: Query<&Transform, With<Player>>,
-    platform_q: Query<&Transform, (With<Platform>, Without<BrownPlatform>)>,
-    mut count_text_query: Query<&mut Text, With<CountText>>,
-) {
-    if let Ok(player_transform) = player_q.get_single() {
-        for platform_transform in platform_q.iter() {
-            let collision = player_transform.translation.y > platform_transform.translation.y
-                && player_transform.translation.y - 50.0 < platform_transform.translation.y
-                && (player_transform.translation.x - platform_transform.translation.x).abs() < 50.0;
-
-            if collision {
-                count.count += 1;
-                for mut text in count_text_query.iter_mut() {
-                    **text = format!("Рахунок: {}", count.count);
-                }
-            }
-        }
-    }
-}
+use bevy::prelude::*;
+use crate::camera::MainCamera;
+use crate::player::*;
+use crate::platforms::{Platform, BrownPlatform, Debris};
+use crate::level::LevelRng;
+use crate::hazards::Hazard;
+use crate::projectiles::Projectile;
+use crate::save::{unix_now, LeaderboardEntry, RunRecord, SaveData};
+use crate::leaderboard::{spawn_name_entry, PendingLeaderboardEntry};
+use crate::persistence::SaveRequested;
+use crate::replay::{LastReplay, ReplayPlayback};
+use crate::events::{BounceSurface, GameOver, MonsterKilled, PlayerBounced, PowerUpCollected, ScoreChanged};
+
+#[derive(Component)]
+pub struct Background {
+    pub index: i16,
+}
+
+#[derive(Component)]
+pub struct GameOverStruct;
+
+#[derive(Component)]
+pub struct PauseText;
+
+#[derive(Component)]
+pub struct StartText;
+
+#[derive(Resource)]
+pub struct Paused(pub bool);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Score(pub u64);
+
+impl Score {
+    pub fn saturating_add(self, other: Score) -> Score {
+        Score(self.0.saturating_add(other.0))
+    }
+
+    // Висота переводиться в очки з насиченням: від'ємна дає нуль, занадто велика дає u64::MAX
+    pub fn from_height(height: f32) -> Score {
+        Score((height / ALTITUDE_PER_POINT) as u64)
+    }
+}
+
+impl std::fmt::Display for Score {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct CountStruct {
+    pub count: Score,
+    pub max_height: f32,
+    pub bonus: Score,
+    pub bounces: u64,
+    pub duration_secs: f32,
+}
+
+// Одне очко за кожні ALTITUDE_PER_POINT пікселів висоти
+pub const ALTITUDE_PER_POINT: f32 = 10.0;
+pub const MONSTER_BONUS: Score = Score(100);
+pub const PICKUP_BONUS: Score = Score(25);
+
+#[derive(Component)]
+pub struct CountText;
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PhysicsSet {
+    // Керування цього кроку: з клавіатури або з повтору, і запис у повтор.
+    // Окремий набір, щоб усі системи Input бачили вже заповнений PlayerInput
+    ReadInput,
+    Input,
+    Movement,
+    Collision,
+    Camera,
+    Generation,
+}
+
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq)]
+pub enum GameOverReason {
+    #[default]
+    Fell,
+    Monster,
+    BlackHole,
+}
+
+impl GameOverReason {
+    pub fn key(self) -> &'static str {
+        match self {
+            GameOverReason::Fell => "fell",
+            GameOverReason::Monster => "monster",
+            GameOverReason::BlackHole => "black_hole",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            GameOverReason::Fell => "Ви впали",
+            GameOverReason::Monster => "Вас з'їв монстр",
+            GameOverReason::BlackHole => "Вас затягнула чорна діра",
+        }
+    }
+}
+
+#[derive(States, Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub enum GameState {
+    #[default]
+    NotStarted,
+    InProcessGame,
+    GameOver,
+    Leaderboard,
+    SkinSelect,
+}
+
+pub fn background_for_game(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let texture = asset_server.load("images/backgrounds/background.png");
+
+    for i in -1..=1 {
+        commands.spawn((
+            Sprite {
+                image: texture.clone(),
+                ..default()
+            },
+            Transform::from_xyz(0.0, i as f32 * 850.0, -1.0),
+            Background { index: i },
+        ));
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn background_follow_camera(
+    camera_q: Query<&Transform, (With<MainCamera>, Without<Background>, Without<Player>)>,
+    mut background_q: Query<(&mut Transform, &mut Background), (Without<MainCamera>, Without<Player>)>,
+) {
+    let Ok(camera_transform) = camera_q.get_single() else { return };
+    let camera_y = camera_transform.translation.y;
+
+    for (mut background_transform, mut background) in background_q.iter_mut() {
+        let background_y = background_transform.translation.y;
+        if camera_y - background_y > 850.0 {
+            background_transform.translation.y += 3.0 * 850.0;
+            background.index += 3;
+        } else if background_y - camera_y > 850.0 {
+            background_transform.translation.y -= 3.0 * 850.0;
+            background.index -= 3;
+        }
+    }
+}
+
+pub fn pause(
+    mut paused: ResMut<Paused>,
+    key_code: Res<ButtonInput<KeyCode>>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    pause_query: Query<Entity, With<PauseText>>
+) {
+    if key_code.just_pressed(KeyCode::KeyP) {
+        paused.0 = !paused.0;
+
+
+        if paused.0 {
+            commands.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                PauseText
+            )).with_children(|parent| {
+                parent.spawn((
+                    Text::new("Пауза"),
+                    TextFont {
+                        font: asset_server.load("fonts/Arsenal-Regular.ttf"),
+                        font_size: 60.0,
+                        ..default()
+                    },
+                    TextColor(Color::BLACK),
+                ));
+            });
+            commands.spawn((
+                AudioPlayer::new(asset_server.load("music/pause.mp3")),
+                PlaybackSettings::ONCE,
+            ));
+        } else {
+            for entity in pause_query.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+pub fn game_over(
+    mut game_over: EventWriter<GameOver>,
+    query: Query<&Transform, With<Player>>,
+    state: Res<State<GameState>>,
+    camera_query: Query<&Transform, (With<MainCamera>, Without<Player>)>,
+) {
+    if *state.get() == GameState::InProcessGame {
+        if let Ok(player_transform) = query.get_single() {
+            if let Ok(camera_transform) = camera_query.get_single() {
+                if player_transform.translation.y < camera_transform.translation.y - 500.0 {
+                    game_over.send(GameOver { reason: GameOverReason::Fell });
+                }
+            }
+        }
+    }
+}
+
+// Забіг закінчується першою причиною, що сталася за крок; решта подій просто вичитується,
+// щоб не дістатися наступному забігу
+pub fn end_run(
+    mut game_over: EventReader<GameOver>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut reason: ResMut<GameOverReason>,
+) {
+    let first = game_over.read().fold(None, |first, event| first.or(Some(event.reason)));
+
+    if let Some(first) = first {
+        *reason = first;
+        next_state.set(GameState::GameOver);
+    }
+}
+
+pub fn play_game_over_sound(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut game_over: EventReader<GameOver>,
+) {
+    if game_over.read().count() > 0 {
+        commands.spawn((
+            AudioPlayer::new(asset_server.load("music/game_over.mp3")),
+            PlaybackSettings::ONCE,
+        ));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn show_game_over(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    state: Res<State<GameState>>,
+    game_over_query: Query<Entity, With<GameOverStruct>>,
+    count: Res<CountStruct>,
+    level_rng: Res<LevelRng>,
+    reason: Res<GameOverReason>,
+    mut save: ResMut<SaveData>,
+    mut pending: ResMut<PendingLeaderboardEntry>,
+    mut save_requests: EventWriter<SaveRequested>,
+    playback: Res<ReplayPlayback>,
+) {
+    if *state.get() == GameState::GameOver && game_over_query.is_empty() {
+        let high_score = Score(save.high_score);
+
+        let is_new_record = count.count > high_score && !playback.is_active();
+
+        // Перегляд повтору не змінює ні статистики, ні таблиці рекордів
+        if !playback.is_active() && save.qualifies_for_leaderboard(count.count.0) {
+            pending.0 = Some(LeaderboardEntry {
+                name: String::new(),
+                score: count.count.0,
+                altitude: count.max_height,
+                seed: level_rng.seed,
+                date: unix_now(),
+                duration_secs: count.duration_secs,
+            });
+        }
+
+        if !playback.is_active() {
+            save.record_run(RunRecord {
+                score: count.count.0,
+                reason: reason.key().to_string(),
+                seed: level_rng.seed,
+            }, count.bounces, count.max_height);
+            save_requests.send(SaveRequested);
+        }
+
+        commands.spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(20.0),
+                ..default()
+            },
+            GameOverStruct
+        )).with_children(|parent| {
+            parent.spawn((
+                Text::new(if playback.is_active() { "Повтор завершено" } else { "Гра програна!" }),
+                TextFont {
+                    font: asset_server.load("fonts/Arsenal-Regular.ttf"),
+                    font_size: 50.0,
+                    ..default()
+                },
+                TextColor(Color::BLACK),
+            ));
+
+            parent.spawn((
+                Text::new(reason.description()),
+                TextFont {
+                    font: asset_server.load("fonts/Arsenal-Italic.ttf"),
+                    font_size: 30.0,
+                    ..default()
+                },
+                TextColor(Color::BLACK),
+            ));
+
+            // Поточний рахунок
+            parent.spawn((
+                Text::new(format!("Ваш рахунок: {}", count.count)),
+                TextFont {
+                    font: asset_server.load("fonts/Mariupol-Bold.ttf"),
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(Color::BLACK),
+            ));
+
+            parent.spawn((
+                Text::new(format!("Стрибків по платформах: {}", count.bounces)),
+                TextFont {
+                    font: asset_server.load("fonts/Mariupol-Regular.ttf"),
+                    font_size: 28.0,
+                    ..default()
+                },
+                TextColor(Color::BLACK),
+            ));
+
+            if is_new_record {
+                parent.spawn((
+                    Text::new("Новий рекорд!"),
+                    TextFont {
+                        font: asset_server.load("fonts/Mariupol-Medium.ttf"),
+                        font_size: 45.0,
+                        ..default()
+                    },
+                    TextColor(Color::BLACK),
+                ));
+            } else {
+                parent.spawn((
+                    Text::new(format!("Рекорд: {}", high_score)),
+                    TextFont {
+                        font: asset_server.load("fonts/Mariupol-Regular.ttf"),
+                        font_size: 35.0,
+                        ..default()
+                    },
+                    TextColor(Color::BLACK),
+                ));
+            }
+
+            parent.spawn((
+                Text::new(format!("Сід рівня: {}", level_rng.seed)),
+                TextFont {
+                    font: asset_server.load("fonts/Arsenal-Regular.ttf"),
+                    font_size: 25.0,
+                    ..default()
+                },
+                TextColor(Color::BLACK),
+            ));
+
+            if pending.0.is_some() {
+                spawn_name_entry(parent, &asset_server);
+            }
+
+            parent.spawn((
+                Text::new("Натисніть R для перезапуску"),
+                TextFont {
+                    font: asset_server.load("fonts/times.ttf"),
+                    font_size: 30.0,
+                    ..default()
+                },
+                TextColor(Color::BLACK),
+            ));
+        });
+    }
+}
+
+pub fn show_start_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    state: Res<State<GameState>>,
+    start_query: Query<Entity, With<StartText>>,
+    last_replay: Res<LastReplay>,
+) {
+    if *state.get() == GameState::NotStarted && start_query.is_empty() {
+        commands.spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(20.0),
+                ..default()
+            },
+            StartText
+        )).with_children(|parent| {
+            parent.spawn((
+                Text::new("Натисніть M для початку гри"),
+                TextFont {
+                    font: asset_server.load("fonts/Arsenal-Regular.ttf"),
+                    font_size: 50.0,
+                    ..default()
+                },
+                TextColor(Color::BLACK),
+            ));
+
+            parent.spawn((
+                Text::new("L — таблиця рекордів"),
+                TextFont {
+                    font: asset_server.load("fonts/Arsenal-Regular.ttf"),
+                    font_size: 28.0,
+                    ..default()
+                },
+                TextColor(Color::BLACK),
+            ));
+
+            parent.spawn((
+                Text::new("C — вибір персонажа"),
+                TextFont {
+                    font: asset_server.load("fonts/Arsenal-Regular.ttf"),
+                    font_size: 28.0,
+                    ..default()
+                },
+                TextColor(Color::BLACK),
+            ));
+
+            if last_replay.0.is_some() {
+                parent.spawn((
+                    Text::new("V — переглянути останній забіг"),
+                    TextFont {
+                        font: asset_server.load("fonts/Arsenal-Regular.ttf"),
+                        font_size: 28.0,
+                        ..default()
+                    },
+                    TextColor(Color::BLACK),
+                ));
+            }
+        });
+    }
+}
+
+pub fn clean_start_screen(
+    mut commands: Commands,
+    start_query: Query<Entity, With<StartText>>,
+) {
+    for entity in start_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn clean_on_restart(
+    mut commands: Commands,
+    player_query: Query<Entity, With<Player>>,
+    platform_query: Query<Entity, With<Platform>>,
+    brown_platform_query: Query<Entity, With<BrownPlatform>>,
+    level_entity_query: Query<Entity, Or<(With<Debris>, With<Hazard>, With<Projectile>)>>,
+    game_over_query: Query<Entity, With<GameOverStruct>>,
+    start_query: Query<Entity, With<StartText>>,
+    pause_query: Query<Entity, With<PauseText>>,
+    mut camera_query: Query<&mut Transform, (With<MainCamera>, Without<Background>)>,
+    mut background_query: Query<(&mut Transform, &Background), (With<Background>, Without<MainCamera>)>,
+    mut count: ResMut<CountStruct>,
+    mut level_rng: ResMut<LevelRng>,
+    mut reason: ResMut<GameOverReason>,
+    mut score_changed: EventWriter<ScoreChanged>,
+    mut shoot_latch: ResMut<ShootLatch>,
+) {
+    for entity in player_query.iter() {
+        let _ = commands.get_entity(entity).map(|entity_commands|
+            entity_commands.try_despawn_recursive()
+        );
+    }
+
+    for entity in platform_query.iter() {
+        let _ = commands.get_entity(entity).map(|entity_commands|
+            entity_commands.try_despawn_recursive()
+        );
+    }
+
+    for entity in brown_platform_query.iter() {
+        let _ = commands.get_entity(entity).map(|mut entity_commands|
+            entity_commands.try_despawn()
+        );
+    }
+
+    // Уламки, вороги та снаряди минулого забігу
+    for entity in level_entity_query.iter() {
+        let _ = commands.get_entity(entity).map(|mut entity_commands|
+            entity_commands.try_despawn()
+        );
+    }
+
+    for entity in game_over_query.iter() {
+        let _ = commands.get_entity(entity).map(|entity_commands|
+            entity_commands.try_despawn_recursive()
+        );
+    }
+
+    for entity in start_query.iter() {
+        let _ = commands.get_entity(entity).map(|entity_commands|
+            entity_commands.try_despawn_recursive()
+        );
+    }
+
+    for entity in pause_query.iter() {
+        let _ = commands.get_entity(entity).map(|entity_commands|
+            entity_commands.try_despawn_recursive()
+        );
+    }
+
+    if let Ok(mut camera_transform) = camera_query.get_single_mut() {
+        camera_transform.translation.y = 0.0;
+    }
+
+    for (mut bg_transform, bg) in background_query.iter_mut() {
+        bg_transform.translation.y = bg.index as f32 * 850.0;
+    }
+
+    *count = CountStruct::default();
+    score_changed.send(ScoreChanged { score: count.count });
+    shoot_latch.0 = false;
+    *reason = GameOverReason::default();
+    level_rng.next_run();
+}
+
+pub fn restart(
+    mut next_state: ResMut<NextState<GameState>>,
+    key_code: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+    pending: Res<PendingLeaderboardEntry>,
+) {
+    // Поки вводиться ім'я, R — це літера, а не перезапуск
+    if key_code.just_pressed(KeyCode::KeyR) && *state.get() == GameState::GameOver && pending.0.is_none() {
+        next_state.set(GameState::NotStarted);
+    }
+}
+
+pub fn start_game(
+    mut next_state: ResMut<NextState<GameState>>,
+    key_code: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>
+) {
+    if key_code.just_pressed(KeyCode::KeyM) && *state.get() == GameState::NotStarted {
+        next_state.set(GameState::InProcessGame);
+    }
+}
+
+pub fn tick_run_time(mut count: ResMut<CountStruct>, time: Res<Time>) {
+    count.duration_secs += time.delta_secs();
+}
+
+pub fn not_paused(paused: Res<Paused>) -> bool {
+    !paused.0
+}
+
+// Після завершення забігу фізика стоїть до зміни стану, щоб повтор закінчувався на тому ж кроці
+pub fn no_pending_transition(next_state: Res<NextState<GameState>>) -> bool {
+    matches!(*next_state, NextState::Unchanged)
+}
+
+pub fn setup_count(mut commands: Commands, count: Res<CountStruct>,
+asset_server: Res<AssetServer>) {
+    commands.spawn((
+        CountText,
+        Text::new(format!("Рахунок: {}", count.count)),
+        TextFont {
+            font: asset_server.load("fonts/Arsenal-Regular.ttf"),
+            font_size: 30.0,
+            ..default()
+        },
+        TextColor(Color::BLACK),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        }
+    ));
+}
+
+pub fn count_bounces(
+    mut bounced: EventReader<PlayerBounced>,
+    mut count: ResMut<CountStruct>,
+) {
+    for event in bounced.read() {
+        if event.surface_kind == BounceSurface::Platform {
+            count.bounces = count.bounces.saturating_add(1);
+        }
+    }
+}
+
+// Усі бонуси до рахунку нараховуються тут, за подіями гри
+pub fn award_bonuses(
+    mut killed: EventReader<MonsterKilled>,
+    mut collected: EventReader<PowerUpCollected>,
+    mut count: ResMut<CountStruct>,
+) {
+    for _ in killed.read() {
+        count.bonus = count.bonus.saturating_add(MONSTER_BONUS);
+    }
+    for _ in collected.read() {
+        count.bonus = count.bonus.saturating_add(PICKUP_BONUS);
+    }
+}
+
+pub fn update_count(
+    mut count: ResMut<CountStruct>,
+    player_q: Query<&Transform, With<Player>>,
+    mut score_changed: EventWriter<ScoreChanged>,
+) {
+    if let Ok(player_transform) = player_q.get_single() {
+        count.max_height = count.max_height.max(player_transform.translation.y - PLAYER_START_Y);
+    }
+
+    let score = Score::from_height(count.max_height).saturating_add(count.bonus);
+    if count.count != score {
+        count.count = score;
+        score_changed.send(ScoreChanged { score });
+    }
+}
+
+pub fn update_count_text(
+    mut score_changed: EventReader<ScoreChanged>,
+    mut count_text_query: Query<&mut Text, With<CountText>>,
+) {
+    if let Some(event) = score_changed.read().last() {
+        for mut text in count_text_query.iter_mut() {
+            **text = format!("Рахунок: {}", event.score);
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use std::path::PathBuf;
+use bevy::prelude::*;
+use bevy::tasks::{block_on, futures_lite::future, IoTaskPool, Task};
+use crate::game::{CountStruct, GameState, PhysicsSet, Score, ALTITUDE_PER_POINT};
+use crate::level::LevelRng;
+use crate::persistence::WriteFileRequested;
+use crate::player::{Player, PLAYER_START_Y};
+use crate::replay::{not_replaying, replay_dir};
+
+const GHOST_MAGIC: &[u8; 4] = b"DJGH";
+const GHOST_VERSION: u8 = 1;
+const GHOST_ALPHA: f32 = 0.4;
+// Привид завжди малюється другим дудлом, щоб не зливатися з гравцем
+const GHOST_SPRITE: &str = "images/players/player2.png";
+// player2.png намальовано носом ліворуч
+const GHOST_FACES_LEFT: bool = true;
+
+// Положення гравця на кожному фіксованому кроці найкращого забігу на цьому сіді
+#[derive(Clone, Debug, Default)]
+pub struct GhostPath {
+    pub seed: u64,
+    pub score: Score,
+    pub points: Vec<Vec2>,
+}
+
+impl GhostPath {
+    // Формат: "DJGH", версія (u8), сід (u64), рахунок (u64), кількість точок (u32),
+    // далі пари координат f32. Усі числа little-endian.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(25 + self.points.len() * 8);
+        bytes.extend_from_slice(GHOST_MAGIC);
+        bytes.push(GHOST_VERSION);
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&self.score.0.to_le_bytes());
+        bytes.extend_from_slice(&(self.points.len() as u32).to_le_bytes());
+        for point in &self.points {
+            bytes.extend_from_slice(&point.x.to_le_bytes());
+            bytes.extend_from_slice(&point.y.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 25 || &bytes[..4] != GHOST_MAGIC {
+            return Err("це не файл привида".to_string());
+        }
+        if bytes[4] != GHOST_VERSION {
+            return Err(format!("невідома версія привида {}", bytes[4]));
+        }
+
+        let seed = u64::from_le_bytes(bytes[5..13].try_into().unwrap_or_default());
+        let score = Score(u64::from_le_bytes(bytes[13..21].try_into().unwrap_or_default()));
+        let count = u32::from_le_bytes(bytes[21..25].try_into().unwrap_or_default()) as usize;
+        let data = &bytes[25..];
+
+        if data.len() != count * 8 {
+            return Err(format!("очікувалося {} точок, файл має {} байтів", count, data.len()));
+        }
+
+        let points = data.chunks_exact(8)
+            .map(|chunk| Vec2::new(
+                f32::from_le_bytes(chunk[..4].try_into().unwrap_or_default()),
+                f32::from_le_bytes(chunk[4..].try_into().unwrap_or_default()),
+            ))
+            .collect();
+
+        Ok(Self { seed, score, points })
+    }
+
+    pub fn load(seed: u64) -> Option<Self> {
+        let path = ghost_path(seed);
+        let bytes = std::fs::read(&path).ok()?;
+
+        match Self::decode(&bytes) {
+            Ok(ghost) if ghost.seed == seed => Some(ghost),
+            Ok(_) => None,
+            Err(error) => {
+                warn!("Не вдалося прочитати привида {:?}: {}", path, error);
+                None
+            }
+        }
+    }
+}
+
+pub fn ghost_path(seed: u64) -> PathBuf {
+    replay_dir().join(format!("best_{}.djg", seed))
+}
+
+#[derive(Resource, Default)]
+pub struct GhostRun {
+    pub best: Option<GhostPath>,
+    // Файл привида читається у фоні, щоб не зупиняти гру на вході в рівень
+    loading: Option<Task<Option<GhostPath>>>,
+    pub tick: usize,
+    pub best_max_height: f32,
+    pub recorded: Vec<Vec2>,
+}
+
+#[derive(Component)]
+pub struct GhostRunner;
+
+#[derive(Component)]
+pub struct GhostDeltaText;
+
+pub struct GhostRunPlugin;
+
+impl Plugin for GhostRunPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GhostRun>()
+            .add_systems(OnEnter(GameState::InProcessGame), load_ghost_run)
+            .add_systems(Update, poll_ghost_load.run_if(in_state(GameState::InProcessGame)))
+            .add_systems(OnExit(GameState::InProcessGame), clean_ghost_run)
+            .add_systems(OnEnter(GameState::GameOver), save_ghost_run.run_if(not_replaying))
+            .add_systems(FixedUpdate, advance_ghost_run.in_set(PhysicsSet::Generation));
+    }
+}
+
+fn load_ghost_run(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    level_rng: Res<LevelRng>,
+    mut ghost_run: ResMut<GhostRun>,
+) {
+    // Найкращий забіг на цьому сіді вже в пам'яті, якщо його щойно пройдено або прочитано
+    let cached = ghost_run.best.take().filter(|best| best.seed == level_rng.seed);
+    *ghost_run = GhostRun::default();
+
+    match cached {
+        Some(best) => {
+            spawn_ghost_runner(&mut commands, &asset_server, &best);
+            ghost_run.best = Some(best);
+        }
+        None => {
+            let seed = level_rng.seed;
+            ghost_run.loading = Some(IoTaskPool::get().spawn(async move { GhostPath::load(seed) }));
+        }
+    }
+}
+
+fn poll_ghost_load(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut ghost_run: ResMut<GhostRun>,
+) {
+    let Some(task) = ghost_run.loading.as_mut() else { return };
+    let Some(best) = block_on(future::poll_once(task)) else { return };
+    ghost_run.loading = None;
+
+    let Some(best) = best else { return };
+    // Кроки, пройдені до завершення читання, теж враховуються в найкращій висоті привида
+    let tick = ghost_run.tick.min(best.points.len());
+    ghost_run.best_max_height = best.points[..tick].iter()
+        .fold(0.0, |max, point| f32::max(max, point.y - PLAYER_START_Y));
+
+    spawn_ghost_runner(&mut commands, &asset_server, &best);
+    ghost_run.best = Some(best);
+}
+
+fn spawn_ghost_runner(commands: &mut Commands, asset_server: &AssetServer, best: &GhostPath) {
+    let start = best.points.first().copied().unwrap_or(Vec2::new(0.0, PLAYER_START_Y));
+
+    commands.spawn((
+        Sprite {
+            image: asset_server.load(GHOST_SPRITE),
+            color: Color::srgba(1.0, 1.0, 1.0, GHOST_ALPHA),
+            flip_x: GHOST_FACES_LEFT,
+            ..default()
+        },
+        Transform::from_xyz(start.x, start.y, 0.45),
+        GhostRunner,
+    ));
+
+    commands.spawn((
+        GhostDeltaText,
+        Text::new(""),
+        TextFont {
+            font: asset_server.load("fonts/Arsenal-Regular.ttf"),
+            font_size: 24.0,
+            ..default()
+        },
+        TextColor(Color::BLACK),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(10.0),
+            ..default()
+        }
+    ));
+}
+
+// Привид іде кроком у крок із гравцем, тож обидва забіги порівнюються в однаковий момент часу
+#[allow(clippy::type_complexity)]
+fn advance_ghost_run(
+    mut ghost_run: ResMut<GhostRun>,
+    count: Res<CountStruct>,
+    player_q: Query<&Transform, With<Player>>,
+    mut runner_q: Query<(&mut Transform, &mut Sprite, &mut Visibility), (With<GhostRunner>, Without<Player>)>,
+    mut text_q: Query<(&mut Text, &mut TextColor), With<GhostDeltaText>>,
+) {
+    if let Ok(player) = player_q.get_single() {
+        ghost_run.recorded.push(player.translation.truncate());
+    }
+
+    let tick = ghost_run.tick;
+    ghost_run.tick += 1;
+    let Some(best) = &ghost_run.best else { return };
+    let point = best.points.get(tick).copied();
+    let previous = tick.checked_sub(1).and_then(|i| best.points.get(i)).copied();
+
+    for (mut t, mut sprite, mut visibility) in runner_q.iter_mut() {
+        match point {
+            Some(point) => {
+                if let Some(previous) = previous {
+                    if point.x != previous.x {
+                        sprite.flip_x = (point.x < previous.x) != GHOST_FACES_LEFT;
+                    }
+                }
+                t.translation.x = point.x;
+                t.translation.y = point.y;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+
+    if let Some(point) = point {
+        ghost_run.best_max_height = ghost_run.best_max_height.max(point.y - PLAYER_START_Y);
+    }
+
+    let delta = ((count.max_height - ghost_run.best_max_height) / ALTITUDE_PER_POINT).round() as i64;
+    let label = format!("Привид: {:+}", delta);
+    let color = if delta >= 0 { Color::srgb(0.1, 0.55, 0.1) } else { Color::srgb(0.75, 0.1, 0.1) };
+
+    for (mut text, mut text_color) in text_q.iter_mut() {
+        if **text != label {
+            **text = label.clone();
+            text_color.0 = color;
+        }
+    }
+}
+
+fn save_ghost_run(
+    mut ghost_run: ResMut<GhostRun>,
+    mut writes: EventWriter<WriteFileRequested>,
+    count: Res<CountStruct>,
+    level_rng: Res<LevelRng>,
+) {
+    // Випадковий сід навряд чи випаде знову, тож привида зберігаємо лише для заданого через --seed
+    if !level_rng.fixed_seed || ghost_run.best.as_ref().is_some_and(|best| best.score >= count.count) {
+        return;
+    }
+
+    let ghost = GhostPath {
+        seed: level_rng.seed,
+        score: count.count,
+        points: std::mem::take(&mut ghost_run.recorded),
+    };
+    writes.send(WriteFileRequested { path: ghost_path(ghost.seed), bytes: ghost.encode() });
+    ghost_run.best = Some(ghost);
+}
+
+#[allow(clippy::type_complexity)]
+fn clean_ghost_run(
+    mut commands: Commands,
+    ghost_q: Query<Entity, Or<(With<GhostRunner>, With<GhostDeltaText>)>>,
+) {
+    for entity in ghost_q.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> GhostPath {
+        GhostPath {
+            seed: 99,
+            score: Score(450),
+            points: vec![Vec2::new(0.0, -150.0), Vec2::new(1.5, -140.25), Vec2::new(-230.0, 812.0)],
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let ghost = sample();
+        let decoded = GhostPath::decode(&ghost.encode()).unwrap();
+
+        assert_eq!(decoded.seed, ghost.seed);
+        assert_eq!(decoded.score.0, ghost.score.0);
+        assert_eq!(decoded.points, ghost.points);
+    }
+
+    #[test]
+    fn rejects_truncated_file() {
+        let bytes = sample().encode();
+
+        for len in [0, 4, 24, 25, bytes.len() - 1, bytes.len() - 8] {
+            assert!(GhostPath::decode(&bytes[..len]).is_err(), "довжина {}", len);
+        }
+    }
+
+    #[test]
+    fn rejects_extra_points() {
+        let mut bytes = sample().encode();
+        bytes.extend_from_slice(&[0; 8]);
+
+        assert!(GhostPath::decode(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_huge_point_count() {
+        let mut bytes = sample().encode();
+        bytes[21..25].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        assert!(GhostPath::decode(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let mut bytes = sample().encode();
+        bytes[4] = GHOST_VERSION + 1;
+
+        assert!(GhostPath::decode(&bytes).is_err());
+    }
+}
@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+use rand::Rng;
+use crate::camera::MainCamera;
+use crate::game::GameOverReason;
+use crate::level::LevelRng;
+use crate::platforms::PLATFORM_X_LIMIT;
+use crate::player::*;
+use crate::powerups::ActivePowerUp;
+use crate::collision::{Collider, Landable, Landed, PreviousTop, Touched};
+use crate::events::{BounceSurface, GameOver, MonsterKilled, PlayerBounced};
+
+#[derive(Component)]
+pub struct Hazard;
+
+#[derive(Component)]
+pub struct Monster {
+    pub velocity_x: f32,
+    pub origin_x: f32,
+    pub range: f32,
+}
+
+#[derive(Component)]
+pub struct BlackHole;
+
+pub const MONSTER_SIZE: Vec2 = Vec2::new(60.0, 44.0);
+pub const BLACK_HOLE_SIZE: f32 = 70.0;
+pub const BLACK_HOLE_PULL_RADIUS: f32 = 170.0;
+pub const BLACK_HOLE_CORE_RADIUS: f32 = 30.0;
+pub const BLACK_HOLE_PULL: f32 = 900.0;
+
+pub fn spawn_monster(commands: &mut Commands, level_rng: &mut LevelRng, x: f32, y: f32) {
+    let speed = level_rng.rng.gen_range(0.0..60.0);
+
+    commands.spawn((
+        Hazard,
+        Monster {
+            velocity_x: speed,
+            origin_x: x,
+            range: level_rng.rng.gen_range(20.0..80.0),
+        },
+        Sprite::from_color(Color::srgb(0.5, 0.2, 0.6), MONSTER_SIZE),
+        Transform::from_xyz(x, y, 0.5),
+        Collider::from_size(MONSTER_SIZE),
+        Landable,
+        PreviousTop::default(),
+    ));
+}
+
+pub fn spawn_black_hole(commands: &mut Commands, x: f32, y: f32) {
+    commands.spawn((
+        Hazard,
+        BlackHole,
+        Sprite::from_color(Color::srgba(0.0, 0.0, 0.0, 0.85), Vec2::splat(BLACK_HOLE_SIZE)),
+        Transform::from_xyz(x, y, 0.4),
+    ));
+}
+
+pub fn move_monsters(
+    mut q: Query<(&mut Transform, &mut Monster)>,
+    time: Res<Time>,
+) {
+    for (mut t, mut monster) in q.iter_mut() {
+        t.translation.x += monster.velocity_x * time.delta_secs();
+
+        let offset = t.translation.x - monster.origin_x;
+        if offset >= monster.range || t.translation.x >= PLATFORM_X_LIMIT {
+            monster.velocity_x = -monster.velocity_x.abs();
+        }
+        if offset <= -monster.range || t.translation.x <= -PLATFORM_X_LIMIT {
+            monster.velocity_x = monster.velocity_x.abs();
+        }
+    }
+}
+
+// Приземлення зверху вбиває монстра
+pub fn stomp_monsters(
+    mut commands: Commands,
+    mut landed: EventReader<Landed>,
+    mut player_q: Query<&mut VelocityInY, With<Player>>,
+    monster_q: Query<(), With<Monster>>,
+    mut bounced: EventWriter<PlayerBounced>,
+    mut killed: EventWriter<MonsterKilled>,
+) {
+    for event in landed.read() {
+        if !monster_q.contains(event.surface) {
+            continue;
+        }
+
+        if let Ok(mut vel_y) = player_q.get_mut(event.player) {
+            vel_y.y = JUMP_VELOCITY;
+            commands.entity(event.surface).despawn();
+            killed.send(MonsterKilled);
+            bounced.send(PlayerBounced {
+                player: event.player,
+                surface_kind: BounceSurface::Monster,
+            });
+        }
+    }
+}
+
+// Будь-який інший дотик до монстра вбиває гравця, якщо той не летить
+pub fn touch_monsters(
+    mut touched: EventReader<Touched>,
+    player_q: Query<Has<ActivePowerUp>, With<Player>>,
+    monster_q: Query<(), With<Monster>>,
+    mut game_over: EventWriter<GameOver>,
+) {
+    for event in touched.read() {
+        if monster_q.contains(event.other) && player_q.get(event.player).is_ok_and(|flying| !flying) {
+            game_over.send(GameOver { reason: GameOverReason::Monster });
+        }
+    }
+}
+
+pub fn black_hole_pull(
+    mut player_q: Query<(&mut Transform, &mut VelocityInY, &mut VelocityInX, Has<ActivePowerUp>), With<Player>>,
+    black_hole_q: Query<&Transform, (With<BlackHole>, Without<Player>)>,
+    mut game_over: EventWriter<GameOver>,
+    time: Res<Time>,
+) {
+    for (mut player, mut vel_y, mut vel_x, flying) in player_q.iter_mut() {
+        if flying {
+            continue;
+        }
+
+        for black_hole in black_hole_q.iter() {
+            let offset = black_hole.translation.truncate() - player.translation.truncate();
+            let distance = offset.length();
+
+            if distance < BLACK_HOLE_CORE_RADIUS {
+                player.translation.x = black_hole.translation.x;
+                player.translation.y = black_hole.translation.y;
+                game_over.send(GameOver { reason: GameOverReason::BlackHole });
+            } else if distance < BLACK_HOLE_PULL_RADIUS {
+                let strength = BLACK_HOLE_PULL * (1.0 - distance / BLACK_HOLE_PULL_RADIUS);
+                let pull = offset / distance * strength * time.delta_secs();
+                vel_x.x += pull.x;
+                vel_y.y += pull.y;
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn remove_hazards_below_camera(
+    mut commands: Commands,
+    hazard_q: Query<(Entity, &Transform), (With<Hazard>, Without<MainCamera>)>,
+    camera_q: Query<&Transform, (With<MainCamera>, Without<Hazard>)>,
+) {
+    let camera_transform = camera_q.single();
+
+    for (entity, transform) in hazard_q.iter() {
+        if transform.translation.y < camera_transform.translation.y - 500.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
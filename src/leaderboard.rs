@@ -0,0 +1,222 @@
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use crate::game::GameState;
+use crate::save::{LeaderboardEntry, SaveData};
+use crate::persistence::SaveRequested;
+
+pub const MAX_NAME_LENGTH: usize = 12;
+
+// Забіг, що потрапляє до таблиці рекордів і чекає на введення імені
+#[derive(Resource, Default)]
+pub struct PendingLeaderboardEntry(pub Option<LeaderboardEntry>);
+
+#[derive(Component)]
+pub struct NameEntryText;
+
+#[derive(Component)]
+pub struct LeaderboardScreen;
+
+fn name_entry_label(name: &str) -> String {
+    format!("Ваше ім'я: {}_ (Enter — зберегти)", name)
+}
+
+pub fn spawn_name_entry(parent: &mut ChildBuilder, asset_server: &AssetServer) {
+    parent.spawn((
+        NameEntryText,
+        Text::new(name_entry_label("")),
+        TextFont {
+            font: asset_server.load("fonts/Arsenal-Regular.ttf"),
+            font_size: 26.0,
+            ..default()
+        },
+        TextColor(Color::BLACK),
+    ));
+}
+
+pub fn name_entry_input(
+    mut events: EventReader<KeyboardInput>,
+    mut pending: ResMut<PendingLeaderboardEntry>,
+    mut save: ResMut<SaveData>,
+    mut text_q: Query<&mut Text, With<NameEntryText>>,
+    mut save_requests: EventWriter<SaveRequested>,
+    state: Res<State<GameState>>,
+) {
+    // На першому кадрі після поразки в черзі ще лежать натискання з гри, наприклад A чи D,
+    // які гравець тримав у момент падіння; до імені вони не потрапляють
+    if state.is_changed() {
+        events.clear();
+        return;
+    }
+
+    let Some(entry) = pending.0.as_mut() else {
+        events.clear();
+        return;
+    };
+
+    let mut submitted = false;
+    for event in events.read() {
+        if !event.state.is_pressed() || event.repeat {
+            continue;
+        }
+
+        match &event.logical_key {
+            Key::Enter => submitted = true,
+            Key::Backspace => {
+                entry.name.pop();
+            }
+            Key::Space if entry.name.chars().count() < MAX_NAME_LENGTH => entry.name.push(' '),
+            Key::Character(chars) => {
+                for c in chars.chars().filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_') {
+                    if entry.name.chars().count() < MAX_NAME_LENGTH {
+                        entry.name.push(c);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let label = if submitted {
+        let mut entry = pending.0.take().unwrap_or_default();
+        entry.name = entry.name.trim().to_string();
+        if entry.name.is_empty() {
+            entry.name = "Гравець".to_string();
+        }
+
+        save.add_leaderboard_entry(entry);
+        save_requests.send(SaveRequested);
+        "Результат додано до таблиці рекордів".to_string()
+    } else {
+        name_entry_label(&entry.name)
+    };
+
+    for mut text in text_q.iter_mut() {
+        if **text != label {
+            **text = label.clone();
+        }
+    }
+}
+
+pub fn toggle_leaderboard(
+    mut next_state: ResMut<NextState<GameState>>,
+    key_code: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+) {
+    match state.get() {
+        GameState::NotStarted if key_code.just_pressed(KeyCode::KeyL) => {
+            next_state.set(GameState::Leaderboard);
+        }
+        GameState::Leaderboard if key_code.just_pressed(KeyCode::KeyL)
+            || key_code.just_pressed(KeyCode::Escape) => {
+            next_state.set(GameState::NotStarted);
+        }
+        _ => {}
+    }
+}
+
+pub fn show_leaderboard(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    save: Res<SaveData>,
+) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(10.0),
+            ..default()
+        },
+        LeaderboardScreen
+    )).with_children(|parent| {
+        parent.spawn((
+            Text::new("Таблиця рекордів"),
+            TextFont {
+                font: asset_server.load("fonts/Arsenal-Bold.ttf"),
+                font_size: 40.0,
+                ..default()
+            },
+            TextColor(Color::BLACK),
+        ));
+
+        if save.leaderboard.is_empty() {
+            parent.spawn((
+                Text::new("Поки що порожньо"),
+                TextFont {
+                    font: asset_server.load("fonts/Arsenal-Italic.ttf"),
+                    font_size: 26.0,
+                    ..default()
+                },
+                TextColor(Color::BLACK),
+            ));
+        }
+
+        for (place, entry) in save.leaderboard.iter().enumerate() {
+            parent.spawn((
+                Text::new(format!(
+                    "{}. {} — {}\n{} · висота {:.0} · {} · сід {}",
+                    place + 1,
+                    entry.name,
+                    entry.score,
+                    format_date(entry.date),
+                    entry.altitude.max(0.0),
+                    format_duration(entry.duration_secs),
+                    entry.seed,
+                )),
+                TextFont {
+                    font: asset_server.load("fonts/Arsenal-Regular.ttf"),
+                    font_size: 17.0,
+                    ..default()
+                },
+                TextColor(Color::BLACK),
+                TextLayout::new_with_justify(JustifyText::Center),
+            ));
+        }
+
+        parent.spawn((
+            Text::new("L або Esc — назад"),
+            TextFont {
+                font: asset_server.load("fonts/times.ttf"),
+                font_size: 24.0,
+                ..default()
+            },
+            TextColor(Color::BLACK),
+        ));
+    });
+}
+
+pub fn clean_leaderboard(
+    mut commands: Commands,
+    screen_q: Query<Entity, With<LeaderboardScreen>>,
+) {
+    for entity in screen_q.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn format_duration(secs: f32) -> String {
+    let total = secs.max(0.0) as u64;
+    format!("{}:{:02}", total / 60, total % 60)
+}
+
+// Перетворення днів від початку епохи в календарну дату (алгоритм Говарда Хіннанта)
+pub fn format_date(unix_secs: u64) -> String {
+    if unix_secs == 0 {
+        return "—".to_string();
+    }
+
+    let days = (unix_secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
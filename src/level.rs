@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+
+#[derive(Resource)]
+pub struct LevelRng {
+    pub seed: u64,
+    pub fixed_seed: bool,
+    pub rng: StdRng,
+}
+
+impl LevelRng {
+    pub fn new(seed: u64, fixed_seed: bool) -> Self {
+        Self {
+            seed,
+            fixed_seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn from_args() -> Self {
+        match seed_from_args() {
+            Some(seed) => Self::new(seed, true),
+            None => Self::new(thread_rng().gen(), false),
+        }
+    }
+
+    /// Повертає генератор на початок послідовності поточного сіду.
+    pub fn reset(&mut self) {
+        self.rng = StdRng::seed_from_u64(self.seed);
+    }
+
+    /// Обирає сід для наступного забігу: заданий з командного рядка лишається незмінним.
+    pub fn next_run(&mut self) {
+        if !self.fixed_seed {
+            self.seed = thread_rng().gen();
+        }
+        self.reset();
+    }
+}
+
+fn seed_from_args() -> Option<u64> {
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let value = if let Some(value) = arg.strip_prefix("--seed=") {
+            Some(value.to_string())
+        } else if arg == "--seed" {
+            args.next()
+        } else {
+            continue;
+        };
+
+        return match value.as_deref().map(str::parse::<u64>) {
+            Some(Ok(seed)) => Some(seed),
+            _ => {
+                warn!("Некоректний сід {:?}, буде використано випадковий", value);
+                None
+            }
+        };
+    }
+
+    None
+}
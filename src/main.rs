@@ -2,12 +2,42 @@ mod game;
 mod camera;
 mod player;
 mod platforms;
+mod level;
+mod difficulty;
+mod powerups;
+mod hazards;
+mod projectiles;
+mod save;
+mod leaderboard;
+mod persistence;
+mod replay;
+mod ghost_run;
+mod skins;
+mod animation;
+mod collision;
+mod events;
 
 use bevy::prelude::*;
 use game::*;
 use camera::*;
 use player::*;
 use crate::platforms::*;
+use crate::level::LevelRng;
+use crate::difficulty::DifficultyCurve;
+use crate::powerups::*;
+use crate::hazards::*;
+use crate::projectiles::*;
+use crate::leaderboard::*;
+use crate::persistence::PersistencePlugin;
+use crate::replay::{not_replaying, ReplayPlugin, REPLAY_TICK_RATE};
+use crate::ghost_run::GhostRunPlugin;
+use crate::skins::SkinsPlugin;
+use crate::animation::animate_player;
+use crate::collision::*;
+use crate::events::GameEventsPlugin;
+
+pub const WINDOW_WIDTH: f32 = 532.0;
+pub const WINDOW_HEIGHT: f32 = 850.0;
 
 fn main() {
     let mut app = App::new();
@@ -15,44 +45,117 @@ fn main() {
         WindowPlugin {
             primary_window: Some(Window {
                 title: "Doodle Jump game".to_string(),
-                resolution: (532., 850.).into(),
+                resolution: (WINDOW_WIDTH, WINDOW_HEIGHT).into(),
                 ..default()
             }),
             ..default()
         }
     ))
+        .add_plugins(PersistencePlugin)
+        .add_plugins(ReplayPlugin)
+        .add_plugins(GhostRunPlugin)
+        .add_plugins(SkinsPlugin)
+        .add_plugins(GameEventsPlugin)
         .insert_state(GameState::NotStarted)
         .init_resource::<CountStruct>()
+        .init_resource::<PlatformGenerator>()
+        .insert_resource(DifficultyCurve::load())
+        .init_resource::<GameOverReason>()
+        .init_resource::<PendingLeaderboardEntry>()
+        .init_resource::<PlayerInput>()
+        .init_resource::<ShootLatch>()
+        .add_event::<Landed>()
+        .add_event::<Touched>()
         .insert_resource(Paused(false))
-        .add_systems(Startup, (background_for_game, camera_setup, setup_count))
+        .insert_resource(LevelRng::from_args())
+        .insert_resource(Time::<Fixed>::from_hz(REPLAY_TICK_RATE as f64))
+        .add_systems(Startup, (
+            background_for_game,
+            camera_setup,
+            setup_count,
+            setup_power_up_text,
+            ))
         .add_systems(OnEnter(GameState::InProcessGame), (load_platforms, load_player))
         .add_systems(OnEnter(GameState::NotStarted), clean_on_restart)
         .add_systems(OnExit(GameState::NotStarted), clean_start_screen)
-        .add_systems(Update, gravity_player
+        .add_systems(OnEnter(GameState::Leaderboard), show_leaderboard)
+        .add_systems(OnExit(GameState::Leaderboard), clean_leaderboard)
+        .configure_sets(FixedUpdate, (
+            PhysicsSet::ReadInput,
+            PhysicsSet::Input,
+            PhysicsSet::Movement,
+            PhysicsSet::Collision,
+            PhysicsSet::Camera,
+            PhysicsSet::Generation,
+            )
+            .chain()
             .run_if(in_state(GameState::InProcessGame))
-            .run_if(not_paused))
-        .add_systems(Update, player_control_in_x
+            .run_if(not_paused)
+            .run_if(no_pending_transition))
+        .add_systems(FixedUpdate, (remember_feet_position, remember_surface_tops, player_control_in_x, shoot).in_set(PhysicsSet::Input))
+        .add_systems(FixedUpdate, (tick_active_power_ups, gravity_player, borders)
+            .chain()
+            .in_set(PhysicsSet::Movement))
+        .add_systems(FixedUpdate, (
+            move_platforms,
+            move_debris,
+            tick_exploding_platforms,
+            move_monsters,
+            move_projectiles,
+            tick_run_time,
+            )
+            .in_set(PhysicsSet::Movement))
+        .add_systems(FixedUpdate, (
+            detect_player_collisions,
+            bounce_on_platforms,
+            break_brown_platforms,
+            bounce_on_power_ups,
+            pick_up_power_ups,
+            stomp_monsters,
+            touch_monsters,
+            black_hole_pull,
+            collision_projectiles_with_hazards,
+            count_bounces,
+            award_bonuses,
+            )
+            .chain()
+            .in_set(PhysicsSet::Collision))
+        .add_systems(FixedUpdate, (move_camera, update_count, game_over)
+            .chain()
+            .in_set(PhysicsSet::Camera))
+        .add_systems(FixedUpdate, (
+            limit_platforms,
+            distance_between_platforms,
+            remove_platforms_below_camera,
+            generate_platforms_for_move_camera,
+            remove_debris_below_camera,
+            remove_hazards_below_camera,
+            remove_projectiles_off_camera,
+            end_run,
+            )
+            .chain()
+            .in_set(PhysicsSet::Generation))
+        .add_systems(Update, (play_jump_sound, play_game_over_sound, spawn_debris, update_count_text, check_platform_sprite_sizes))
+        .add_systems(Update, update_player_ghost.run_if(in_state(GameState::InProcessGame)))
+        .add_systems(Update, latch_shoot_input
+            .run_if(in_state(GameState::InProcessGame))
+            .run_if(not_paused)
+            .run_if(not_replaying))
+        .add_systems(Update, animate_player
+            .before(update_player_ghost)
             .run_if(in_state(GameState::InProcessGame))
             .run_if(not_paused))
-        .add_systems(Update, limit_platforms.run_if(in_state(GameState::InProcessGame)))
-        .add_systems(Update, (
-            collision_player_with_brown_platforms,
-            collision_player_with_platforms,
-            ))
-        .add_systems(Update, borders.run_if(in_state(GameState::InProcessGame)))
-        .add_systems(Update, move_camera)
-        .add_systems(Update, remove_platforms_below_camera
-                                  .run_if(in_state(GameState::InProcessGame)))
-        .add_systems(Update, generate_platforms_for_move_camera
-            .run_if(in_state(GameState::InProcessGame)))
-        .add_systems(Update, (background_follow_camera, distance_between_platforms))
-        .add_systems(Update, pause)
-        .add_systems(Update, game_over)
+        .add_systems(Update, background_follow_camera)
+        .add_systems(Update, update_power_up_text)
+        .add_systems(Update, pause.run_if(in_state(GameState::InProcessGame)))
         .add_systems(Update, show_game_over)
         .add_systems(Update, show_start_screen)
         .add_systems(Update, restart)
         .add_systems(Update, start_game)
-        .add_systems(Update, update_count.run_if(in_state(GameState::InProcessGame)))
+        .add_systems(Update, toggle_leaderboard)
+        .add_systems(Update, name_entry_input
+            .after(show_game_over)
+            .run_if(in_state(GameState::GameOver)))
         .run();
 }
  
\ No newline at end of file
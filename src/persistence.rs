@@ -0,0 +1,219 @@
+use std::path::PathBuf;
+use bevy::prelude::*;
+use bevy::tasks::{block_on, futures_lite::future, IoTaskPool, Task};
+use crate::save::{save_path, write_atomically, SaveData};
+
+// Системи змінюють ресурс SaveData і надсилають цю подію; запис на диск відбувається у фоні
+#[derive(Event)]
+pub struct SaveRequested;
+
+// Окремі файли поза збереженням (повтори, привиди) пишуться у фоні так само,
+// а помилки запису надходять тим самим SaveFailed
+#[derive(Event)]
+pub struct WriteFileRequested {
+    pub path: PathBuf,
+    pub bytes: Vec<u8>,
+}
+
+#[derive(Event)]
+pub struct SaveFailed {
+    pub message: String,
+}
+
+#[derive(Resource, Default)]
+struct SaveWriter {
+    task: Option<Task<std::io::Result<()>>>,
+    // Запит надійшов, поки попередній запис ще триває
+    dirty: bool,
+}
+
+#[derive(Resource, Default)]
+struct FileWriter {
+    tasks: Vec<(PathBuf, Task<std::io::Result<()>>)>,
+}
+
+#[derive(Component)]
+pub struct SaveErrorNotice {
+    pub timer: Timer,
+}
+
+pub struct PersistencePlugin;
+
+impl Plugin for PersistencePlugin {
+    fn build(&self, app: &mut App) {
+        let (save, needs_write) = SaveData::load();
+
+        app.insert_resource(save)
+            .init_resource::<SaveWriter>()
+            .init_resource::<FileWriter>()
+            .add_event::<SaveRequested>()
+            .add_event::<WriteFileRequested>()
+            .add_event::<SaveFailed>()
+            .add_systems(Startup, apply_settings)
+            .add_systems(Update, (
+                write_save_in_background,
+                poll_save_task,
+                write_files_in_background,
+                poll_file_writes,
+                show_save_errors,
+                ).chain())
+            .add_systems(Last, flush_on_exit);
+
+        if needs_write {
+            app.add_systems(Startup, |mut requests: EventWriter<SaveRequested>| {
+                requests.send(SaveRequested);
+            });
+        }
+    }
+}
+
+fn apply_settings(save: Res<SaveData>, mut volume: ResMut<GlobalVolume>) {
+    volume.volume = bevy::audio::Volume::new(save.settings.volume.clamp(0.0, 1.0));
+}
+
+fn write_save_in_background(
+    mut requests: EventReader<SaveRequested>,
+    mut writer: ResMut<SaveWriter>,
+    mut failures: EventWriter<SaveFailed>,
+    save: Res<SaveData>,
+) {
+    if requests.read().count() > 0 {
+        writer.dirty = true;
+    }
+
+    // Одночасно виконується лише один запис, щоб тимчасові файли не перетиналися
+    if !writer.dirty || writer.task.is_some() {
+        return;
+    }
+    writer.dirty = false;
+
+    let contents = match save.to_ron() {
+        Ok(contents) => contents,
+        Err(error) => {
+            failures.send(SaveFailed { message: error.to_string() });
+            return;
+        }
+    };
+
+    let path = save_path();
+    writer.task = Some(IoTaskPool::get().spawn(async move {
+        write_atomically(&path, contents.as_bytes(), true)
+    }));
+}
+
+fn poll_save_task(
+    mut writer: ResMut<SaveWriter>,
+    mut failures: EventWriter<SaveFailed>,
+) {
+    let Some(task) = writer.task.as_mut() else { return };
+    let Some(result) = block_on(future::poll_once(task)) else { return };
+
+    writer.task = None;
+    if let Err(error) = result {
+        failures.send(SaveFailed { message: error.to_string() });
+    }
+}
+
+fn write_files_in_background(
+    mut requests: EventReader<WriteFileRequested>,
+    mut writer: ResMut<FileWriter>,
+) {
+    for request in requests.read() {
+        let (path, bytes) = (request.path.clone(), request.bytes.clone());
+        let task = IoTaskPool::get().spawn(async move {
+            write_atomically(&path, &bytes, false)
+        });
+        writer.tasks.push((request.path.clone(), task));
+    }
+}
+
+fn poll_file_writes(
+    mut writer: ResMut<FileWriter>,
+    mut failures: EventWriter<SaveFailed>,
+) {
+    writer.tasks.retain_mut(|(path, task)| {
+        let Some(result) = block_on(future::poll_once(task)) else { return true };
+        if let Err(error) = result {
+            failures.send(SaveFailed { message: format!("{:?}: {}", path, error) });
+        }
+        false
+    });
+}
+
+// Перед виходом дочікуємося фонового запису і дописуємо відкладені зміни
+fn flush_on_exit(
+    mut exits: EventReader<AppExit>,
+    mut requests: EventReader<SaveRequested>,
+    mut writer: ResMut<SaveWriter>,
+    mut file_writer: ResMut<FileWriter>,
+    save: Res<SaveData>,
+) {
+    if exits.read().count() == 0 {
+        return;
+    }
+
+    for (path, task) in file_writer.tasks.drain(..) {
+        if let Err(error) = block_on(task) {
+            error!("Не вдалося записати {:?}: {}", path, error);
+        }
+    }
+    if requests.read().count() > 0 {
+        writer.dirty = true;
+    }
+
+    if let Some(task) = writer.task.take() {
+        if let Err(error) = block_on(task) {
+            error!("Не вдалося записати збереження: {}", error);
+        }
+    }
+
+    if writer.dirty {
+        writer.dirty = false;
+        let result = save.to_ron()
+            .map_err(|error| error.to_string())
+            .and_then(|contents| write_atomically(&save_path(), contents.as_bytes(), true)
+                .map_err(|error| error.to_string()));
+        if let Err(error) = result {
+            error!("Не вдалося записати збереження: {}", error);
+        }
+    }
+}
+
+fn show_save_errors(
+    mut commands: Commands,
+    mut failures: EventReader<SaveFailed>,
+    mut notice_q: Query<(Entity, &mut SaveErrorNotice)>,
+    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+) {
+    for failure in failures.read() {
+        error!("Не вдалося записати збереження: {}", failure.message);
+
+        for (entity, _) in notice_q.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        commands.spawn((
+            SaveErrorNotice { timer: Timer::from_seconds(4.0, TimerMode::Once) },
+            Text::new("Не вдалося зберегти прогрес"),
+            TextFont {
+                font: asset_server.load("fonts/Arsenal-Regular.ttf"),
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.7, 0.0, 0.0)),
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(10.0),
+                left: Val::Px(10.0),
+                ..default()
+            },
+        ));
+    }
+
+    for (entity, mut notice) in notice_q.iter_mut() {
+        notice.timer.tick(time.delta());
+        if notice.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
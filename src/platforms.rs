@@ -1,150 +1,496 @@
-use bevy::prelude::*;
-use rand::{thread_rng, Rng};
-use crate::camera::MainCamera;
-use crate::player::*;
-
-#[derive(Component)]
-pub struct Platform;
-
-#[derive(Component)]
-pub struct BrownPlatform;
-
-pub fn load_platforms(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let mut cur_y = -200.0;
-
-    for _ in 1..=30 {
-        let b = thread_rng().gen_bool(0.3);
-        spawn_platform(&mut commands, &asset_server, cur_y, b);
-        cur_y += thread_rng().gen_range(10.0..300.0);
-    }
-}
-
-fn spawn_platform(commands: &mut Commands, asset_server: &Res<AssetServer>, y: f32,
-brown: bool) {
-    let x = thread_rng().gen_range(-240.0..240.0);
-
-    let path = if brown {
-        "images/platforms/platform_1.png".to_string()
-    } else {
-        format!(
-            "images/platforms/platform_{}.png",
-            thread_rng().gen_range(2..4)
-        )
-    };
-
-    let mut entity = commands.spawn((
-        Platform,
-        Sprite {
-            image: asset_server.load(path),
-            ..default()
-        },
-        Transform::from_xyz(x, y, 0.5),
-    ));
-
-    if brown {
-        entity.insert(BrownPlatform);
-    }
-}
-
-pub fn limit_platforms(
-    mut q: Query<&mut Transform, With<Platform>>,
-) {
-    for mut t in q.iter_mut() {
-        if t.translation.x > 240.0 {
-            t.translation.x = 240.0;
-        }
-        if t.translation.x < -240.0 {
-            t.translation.x = -240.0;
-        }
-    }
-}
-
-pub fn distance_between_platforms(
-    mut platform_q: Query<(Entity, &mut Transform), With<Platform>>,
-) {
-    let min_distance = 40.0;
-
-    let platforms: Vec<(Entity, Transform)> = platform_q.iter_mut()
-        .map(|(e, t)| (e, *t))
-        .collect();
-
-    for (entity_a, mut transform_a) in platform_q.iter_mut() {
-        for (entity_b, transform_b) in &platforms {
-            if entity_a != *entity_b {
-                let distance = transform_a.translation.distance(transform_b.translation);
-                if distance < min_distance {
-                    let direction = (transform_a.translation - transform_b.translation).normalize();
-                    transform_a.translation += direction * (min_distance - distance);
-                }
-            }
-        }
-    }
-}
-
-pub fn generate_platforms_for_move_camera(
-    mut commands: Commands,
-    platform_q: Query<&Transform, (With<Platform>, Without<MainCamera>, Without<Player>)>,
-    camera_q: Query<&Transform, (With<MainCamera>, Without<Platform>, Without<Player>)>,
-    asset_server: Res<AssetServer>,
-) {
-    let camera_transform = camera_q.single();
-    let mut max_y = -600.0;
-
-    for transform in platform_q.iter() {
-        if transform.translation.y > max_y {
-            max_y = transform.translation.y;
-        }
-    }
-
-    if max_y < camera_transform.translation.y + 40.0 {
-        let mut cur_y = max_y + thread_rng().gen_range(10.0..300.0);
-
-        for _ in 1..30 {
-            let b = thread_rng().gen_bool(0.3);
-            spawn_platform(&mut commands, &asset_server, cur_y, b);
-            cur_y += thread_rng().gen_range(10.0..300.0);
-        }
-    }
-}
-
-pub fn remove_platforms_below_camera(
-    mut commands: Commands,
-    platform_q: Query<(Entity, &Transform), (With<Platform>, Without<MainCamera>, Without<Player>)>,
-    camera_q: Query<&Transform, (With<MainCamera>, Without<Platform>, Without<Player>)>,
-) {
-    let camera_transform = camera_q.single();
-
-    for (entity, transform) in platform_q.iter() {
-        if transform.translation.y < camera_transform.translation.y - 500.0 {
-            commands.entity(entity).despawn();
-        }
-    }
-}
-
-pub fn collision_player_with_brown_platforms (
-    mut commands: Commands,
-    mut player_q: Query<&Transform, With<Player>>,
-    brown_platform_q: Query<(Entity, &Transform), With<BrownPlatform>>,
-    asset_server: Res<AssetServer>
-) {
-    for player in player_q.iter_mut() {
-        for (entity, platform) in brown_platform_q.iter() {
-            let collision = player.translation.y > platform.translation.y
-                && player.translation.y - 20.0 < platform.translation.y
-                && (player.translation.x - platform.translation.x).abs() < 20.0;
-
-            if collision {
-                commands.spawn((
-                    Sprite {
-                        image: asset_server.load("images/platforms/platform_4.png"),
-                        ..default()
-                    },
-                    Transform::from_xyz(platform.translation.x, platform.translation.y, 0.5),
-                ));
-
-                commands.entity(entity).despawn();
-            }
-        }
-    }
-}
- 
\ No newline at end of file
+use bevy::prelude::*;
+use rand::Rng;
+use crate::camera::MainCamera;
+use crate::player::*;
+use crate::level::LevelRng;
+use crate::difficulty::DifficultyCurve;
+use crate::powerups::maybe_spawn_power_up;
+use crate::hazards::{spawn_monster, spawn_black_hole};
+use crate::collision::{Collider, Landable, Landed, PassThrough, PreviousTop};
+use crate::events::PlatformBroken;
+use crate::{WINDOW_WIDTH, WINDOW_HEIGHT};
+
+#[derive(Component)]
+pub struct Platform;
+
+#[derive(Component)]
+pub struct BrownPlatform;
+
+#[derive(Component)]
+pub struct MovingPlatform {
+    pub velocity: Vec2,
+    pub origin: Vec2,
+    pub range: f32,
+}
+
+#[derive(Component)]
+pub struct VanishingPlatform;
+
+#[derive(Component)]
+pub struct ExplodingPlatform {
+    pub timer: Timer,
+    pub armed: bool,
+}
+
+#[derive(Component)]
+pub struct Debris {
+    pub velocity: Vec2,
+    pub angular_velocity: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlatformKind {
+    Normal,
+    Brown,
+    MovingHorizontal,
+    MovingVertical,
+    Vanishing,
+    Exploding,
+}
+
+impl PlatformKind {
+    // Платформи, на які гарантовано можна приземлитися хоча б раз
+    pub fn is_reliable(self) -> bool {
+        matches!(self, PlatformKind::Normal | PlatformKind::Vanishing)
+    }
+}
+
+pub const EXPLOSION_WARNING_SECS: f32 = 1.0;
+pub const MIN_HAZARD_SPACING: f32 = 600.0;
+const EXPLODING_COLOR: Color = Color::srgb(1.0, 0.55, 0.2);
+const EXPLODING_BLINK_COLOR: Color = Color::srgb(1.0, 0.1, 0.1);
+
+#[derive(Resource, Default)]
+pub struct PlatformGenerator {
+    pub last_solid: Option<Vec2>,
+    pub last_hazard_y: f32,
+}
+
+pub const PLATFORM_HALF_WIDTH: f32 = 26.0;
+// Розміри спрайтів platform_1.png ... platform_3.png. Колайдер не можна брати з самого зображення:
+// воно вантажиться асинхронно, а зіткнення мають бути однаковими з першого тіку для повторів.
+// Відповідність таблиці файлам перевіряє check_platform_sprite_sizes.
+pub const PLATFORM_SPRITE_SIZES: [Vec2; 3] = [
+    Vec2::new(53.0, 15.0),
+    Vec2::new(49.0, 13.0),
+    Vec2::new(50.0, 14.0),
+];
+pub const PLATFORM_X_LIMIT: f32 = WINDOW_WIDTH / 2.0 - PLATFORM_HALF_WIDTH;
+
+fn platform_sprite_path(sprite_index: usize) -> String {
+    format!("images/platforms/platform_{}.png", sprite_index)
+}
+
+pub fn check_platform_sprite_sizes(
+    mut events: EventReader<AssetEvent<Image>>,
+    images: Res<Assets<Image>>,
+    asset_server: Res<AssetServer>,
+) {
+    for event in events.read() {
+        let AssetEvent::LoadedWithDependencies { id } = event else { continue };
+        let (Some(path), Some(image)) = (asset_server.get_path(*id), images.get(*id)) else { continue };
+
+        for (index, expected) in PLATFORM_SPRITE_SIZES.iter().enumerate() {
+            if path.path().to_str() == Some(platform_sprite_path(index + 1).as_str()) && image.size_f32() != *expected {
+                warn!("Спрайт {} має розмір {}, а колайдер платформи розраховано на {}", path, image.size_f32(), expected);
+            }
+        }
+    }
+}
+
+pub fn load_platforms(mut commands: Commands, asset_server: Res<AssetServer>,
+mut level_rng: ResMut<LevelRng>, mut generator: ResMut<PlatformGenerator>,
+curve: Res<DifficultyCurve>) {
+    level_rng.reset();
+    *generator = PlatformGenerator::default();
+
+    spawn_platforms_from(&mut commands, &asset_server, &mut level_rng, &mut generator, &curve, -200.0, 30);
+}
+
+// Запас на реакцію гравця та на зсув платформ у distance_between_platforms
+fn safe_jump_height() -> f32 {
+    max_jump_height() * 0.8
+}
+
+fn wrapped_distance(a: f32, b: f32) -> f32 {
+    let distance = (a - b).abs();
+    distance.min(WINDOW_WIDTH - distance)
+}
+
+fn spawn_platforms_from(commands: &mut Commands, asset_server: &Res<AssetServer>,
+level_rng: &mut LevelRng, generator: &mut PlatformGenerator, curve: &DifficultyCurve,
+start_y: f32, count: usize) {
+    let mut cur_y = start_y;
+
+    for _ in 0..count {
+        generate_platform(commands, asset_server, level_rng, generator, curve, cur_y);
+        cur_y += next_gap(level_rng, curve, cur_y);
+    }
+}
+
+fn max_gap_at(curve: &DifficultyCurve, y: f32) -> f32 {
+    curve.at(y).max_gap.min(safe_jump_height())
+}
+
+fn next_gap(level_rng: &mut LevelRng, curve: &DifficultyCurve, y: f32) -> f32 {
+    let max_gap = max_gap_at(curve, y);
+    let min_gap = curve.at(y).min_gap.min(max_gap - 1.0);
+    level_rng.rng.gen_range(min_gap..max_gap)
+}
+
+fn generate_platform(commands: &mut Commands, asset_server: &Res<AssetServer>,
+level_rng: &mut LevelRng, generator: &mut PlatformGenerator, curve: &DifficultyCurve, y: f32) {
+    let difficulty = curve.at(y);
+
+    // Якщо ця платформа буде ламкою, наступна може опинитися вже поза досяжністю
+    let must_be_solid = match generator.last_solid {
+        Some(last) => y + max_gap_at(curve, y) - last.y > safe_jump_height(),
+        None => true,
+    };
+    let kind = if must_be_solid {
+        PlatformKind::Normal
+    } else {
+        let mut roll = level_rng.rng.gen::<f64>();
+        let weights = [
+            (PlatformKind::Brown, difficulty.brown_chance),
+            (PlatformKind::MovingHorizontal, difficulty.moving_chance / 2.0),
+            (PlatformKind::MovingVertical, difficulty.moving_chance / 2.0),
+            (PlatformKind::Vanishing, difficulty.vanishing_chance),
+            (PlatformKind::Exploding, difficulty.exploding_chance),
+        ];
+
+        let mut kind = PlatformKind::Normal;
+        for (candidate, chance) in weights {
+            if roll < chance {
+                kind = candidate;
+                break;
+            }
+            roll -= chance;
+        }
+        kind
+    };
+    let mut x = level_rng.rng.gen_range(-PLATFORM_X_LIMIT..PLATFORM_X_LIMIT);
+
+    // Рухомі й вибухові платформи не вважаються опорою: вони можуть зникнути з досяжної зони
+    if kind.is_reliable() {
+        let (from_x, reach) = match generator.last_solid {
+            Some(last) => (last.x, max_horizontal_reach(y - last.y)),
+            None => (0.0, PLAYER_HALF_WIDTH),
+        };
+
+        if wrapped_distance(x, from_x) > reach {
+            let offset = if reach > 1.0 { level_rng.rng.gen_range(-reach..reach) } else { 0.0 };
+            x = from_x + offset;
+            if x > WINDOW_WIDTH / 2.0 {
+                x -= WINDOW_WIDTH;
+            }
+            if x < -WINDOW_WIDTH / 2.0 {
+                x += WINDOW_WIDTH;
+            }
+            x = x.clamp(-PLATFORM_X_LIMIT, PLATFORM_X_LIMIT);
+        }
+
+        generator.last_solid = Some(Vec2::new(x, y));
+    }
+
+    let platform = spawn_platform(commands, asset_server, level_rng, x, y, kind);
+    if kind == PlatformKind::Normal {
+        maybe_spawn_power_up(commands, level_rng, platform);
+    }
+
+    maybe_spawn_hazard(commands, level_rng, generator, difficulty.monster_chance,
+        difficulty.black_hole_chance, y);
+}
+
+// Небезпеки ставляться на протилежному від останньої опори боці екрана, щоб не перекривати шлях
+fn maybe_spawn_hazard(commands: &mut Commands, level_rng: &mut LevelRng, generator: &mut PlatformGenerator,
+monster_chance: f64, black_hole_chance: f64, y: f32) {
+    let Some(last_solid) = generator.last_solid else { return };
+    if y - generator.last_hazard_y < MIN_HAZARD_SPACING {
+        return;
+    }
+
+    let roll = level_rng.rng.gen::<f64>();
+    if roll >= monster_chance + black_hole_chance {
+        return;
+    }
+
+    let mut x = last_solid.x + WINDOW_WIDTH / 2.0;
+    if x > WINDOW_WIDTH / 2.0 {
+        x -= WINDOW_WIDTH;
+    }
+    x = x.clamp(-PLATFORM_X_LIMIT, PLATFORM_X_LIMIT);
+    let hazard_y = y + 60.0;
+
+    if roll < monster_chance {
+        spawn_monster(commands, level_rng, x, hazard_y);
+    } else {
+        spawn_black_hole(commands, x, hazard_y);
+    }
+    generator.last_hazard_y = hazard_y;
+}
+
+fn spawn_platform(commands: &mut Commands, asset_server: &Res<AssetServer>,
+level_rng: &mut LevelRng, x: f32, y: f32, kind: PlatformKind) -> Entity {
+    let sprite_index = match kind {
+        PlatformKind::Brown => 1,
+        PlatformKind::MovingHorizontal | PlatformKind::MovingVertical | PlatformKind::Vanishing
+            | PlatformKind::Exploding => 2,
+        PlatformKind::Normal => level_rng.rng.gen_range(2..4),
+    };
+    let path = platform_sprite_path(sprite_index);
+
+    // Кольори різновидів платформ, як в оригінальній грі
+    let color = match kind {
+        PlatformKind::MovingHorizontal | PlatformKind::MovingVertical => Color::srgb(0.4, 0.6, 1.0),
+        PlatformKind::Vanishing => Color::srgba(1.0, 1.0, 1.0, 0.45),
+        PlatformKind::Exploding => EXPLODING_COLOR,
+        _ => Color::WHITE,
+    };
+
+    let mut entity = commands.spawn((
+        Platform,
+        Sprite {
+            image: asset_server.load(path),
+            color,
+            ..default()
+        },
+        Transform::from_xyz(x, y, 0.5),
+        Collider::from_size(PLATFORM_SPRITE_SIZES[sprite_index - 1]),
+        Landable,
+        PreviousTop::default(),
+    ));
+
+    match kind {
+        PlatformKind::Normal => {}
+        PlatformKind::Brown => {
+            entity.insert((BrownPlatform, PassThrough));
+        }
+        PlatformKind::Vanishing => {
+            entity.insert(VanishingPlatform);
+        }
+        PlatformKind::Exploding => {
+            let fuse = level_rng.rng.gen_range(2.5..4.0);
+            entity.insert(ExplodingPlatform {
+                timer: Timer::from_seconds(fuse, TimerMode::Once),
+                armed: false,
+            });
+        }
+        PlatformKind::MovingHorizontal | PlatformKind::MovingVertical => {
+            let (velocity, range) = if kind == PlatformKind::MovingHorizontal {
+                let speed = level_rng.rng.gen_range(60.0..140.0);
+                let direction = if level_rng.rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+                (Vec2::new(speed * direction, 0.0), PLATFORM_X_LIMIT * 2.0)
+            } else {
+                (Vec2::new(0.0, level_rng.rng.gen_range(50.0..100.0)), level_rng.rng.gen_range(60.0..120.0))
+            };
+
+            entity.insert(MovingPlatform {
+                velocity,
+                origin: Vec2::new(x, y),
+                range,
+            });
+        }
+    }
+
+    entity.id()
+}
+
+pub fn move_platforms(
+    mut q: Query<(&mut Transform, &mut MovingPlatform)>,
+    time: Res<Time>,
+) {
+    for (mut t, mut moving) in q.iter_mut() {
+        t.translation += (moving.velocity * time.delta_secs()).extend(0.0);
+
+        let offset = t.translation.truncate() - moving.origin;
+        if t.translation.x >= PLATFORM_X_LIMIT || offset.x >= moving.range {
+            moving.velocity.x = -moving.velocity.x.abs();
+        }
+        if t.translation.x <= -PLATFORM_X_LIMIT || offset.x <= -moving.range {
+            moving.velocity.x = moving.velocity.x.abs();
+        }
+        if offset.y >= moving.range {
+            moving.velocity.y = -moving.velocity.y.abs();
+        }
+        if offset.y <= -moving.range {
+            moving.velocity.y = moving.velocity.y.abs();
+        }
+    }
+}
+
+pub fn limit_platforms(
+    mut q: Query<&mut Transform, With<Platform>>,
+) {
+    for mut t in q.iter_mut() {
+        t.translation.x = t.translation.x.clamp(-PLATFORM_X_LIMIT, PLATFORM_X_LIMIT);
+    }
+}
+
+pub fn distance_between_platforms(
+    mut platform_q: Query<(Entity, &mut Transform), With<Platform>>,
+) {
+    let min_distance = 40.0;
+
+    let platforms: Vec<(Entity, Transform)> = platform_q.iter_mut()
+        .map(|(e, t)| (e, *t))
+        .collect();
+
+    for (entity_a, mut transform_a) in platform_q.iter_mut() {
+        for (entity_b, transform_b) in &platforms {
+            if entity_a != *entity_b {
+                let distance = transform_a.translation.distance(transform_b.translation);
+                if distance < min_distance {
+                    let direction = (transform_a.translation - transform_b.translation).normalize();
+                    transform_a.translation += direction * (min_distance - distance);
+                }
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn generate_platforms_for_move_camera(
+    mut commands: Commands,
+    platform_q: Query<&Transform, (With<Platform>, Without<MainCamera>, Without<Player>)>,
+    camera_q: Query<&Transform, (With<MainCamera>, Without<Platform>, Without<Player>)>,
+    asset_server: Res<AssetServer>,
+    mut level_rng: ResMut<LevelRng>,
+    mut generator: ResMut<PlatformGenerator>,
+    curve: Res<DifficultyCurve>,
+) {
+    let camera_transform = camera_q.single();
+    let mut max_y = -600.0;
+
+    for transform in platform_q.iter() {
+        if transform.translation.y > max_y {
+            max_y = transform.translation.y;
+        }
+    }
+
+    if max_y < camera_transform.translation.y + 40.0 {
+        let start_y = max_y + next_gap(&mut level_rng, &curve, max_y);
+        spawn_platforms_from(&mut commands, &asset_server, &mut level_rng, &mut generator, &curve, start_y, 29);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn remove_platforms_below_camera(
+    mut commands: Commands,
+    platform_q: Query<(Entity, &Transform), (With<Platform>, Without<MainCamera>, Without<Player>)>,
+    camera_q: Query<&Transform, (With<MainCamera>, Without<Platform>, Without<Player>)>,
+) {
+    let camera_transform = camera_q.single();
+
+    for (entity, transform) in platform_q.iter() {
+        if transform.translation.y < camera_transform.translation.y - 500.0 {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+pub fn break_brown_platforms(
+    mut commands: Commands,
+    mut landed: EventReader<Landed>,
+    brown_platform_q: Query<&Transform, With<BrownPlatform>>,
+    mut broken: EventWriter<PlatformBroken>,
+) {
+    for event in landed.read() {
+        // Гравець пролітає крізь ламку платформу, а вона розвалюється навпіл
+        if let Ok(platform) = brown_platform_q.get(event.surface) {
+            broken.send(PlatformBroken { position: platform.translation, color: Color::WHITE });
+            commands.entity(event.surface).despawn();
+        }
+    }
+}
+
+// Зламана платформа розлітається двома половинками
+pub fn spawn_debris(
+    mut commands: Commands,
+    mut broken: EventReader<PlatformBroken>,
+    asset_server: Res<AssetServer>,
+) {
+    for &PlatformBroken { position, color } in broken.read() {
+        let texture = asset_server.load("images/platforms/platform_4.png");
+
+        for (rect, side) in [
+            (Rect::new(0.0, 0.0, 31.0, 25.0), -1.0),
+            (Rect::new(31.0, 0.0, 62.0, 25.0), 1.0),
+        ] {
+            commands.spawn((
+                Debris {
+                    velocity: Vec2::new(side * 40.0, 60.0),
+                    angular_velocity: -side * 3.0,
+                },
+                Sprite {
+                    image: texture.clone(),
+                    rect: Some(rect),
+                    color,
+                    ..default()
+                },
+                Transform::from_xyz(position.x + side * 15.5, position.y, 0.6),
+            ));
+        }
+    }
+}
+
+// Відлік вибухової платформи починається, коли вона з'являється на екрані
+pub fn tick_exploding_platforms(
+    mut commands: Commands,
+    mut platform_q: Query<(Entity, &Transform, &mut ExplodingPlatform, &mut Sprite), Without<MainCamera>>,
+    camera_q: Query<&Transform, (With<MainCamera>, Without<Platform>)>,
+    mut broken: EventWriter<PlatformBroken>,
+    time: Res<Time>,
+) {
+    let camera_transform = camera_q.single();
+    let top_of_screen = camera_transform.translation.y + WINDOW_HEIGHT / 2.0;
+
+    for (entity, transform, mut exploding, mut sprite) in platform_q.iter_mut() {
+        if !exploding.armed {
+            exploding.armed = transform.translation.y < top_of_screen;
+            continue;
+        }
+
+        exploding.timer.tick(time.delta());
+        if exploding.timer.finished() {
+            broken.send(PlatformBroken { position: transform.translation, color: EXPLODING_BLINK_COLOR });
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let remaining = exploding.timer.remaining_secs();
+        sprite.color = if remaining < EXPLOSION_WARNING_SECS && ((remaining * 10.0) as u32).is_multiple_of(2) {
+            EXPLODING_BLINK_COLOR
+        } else {
+            EXPLODING_COLOR
+        };
+    }
+}
+
+pub fn move_debris(
+    mut q: Query<(&mut Transform, &mut Debris)>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+
+    for (mut t, mut debris) in q.iter_mut() {
+        debris.velocity.y += GRAVITY * 2.0 * dt;
+        t.translation += (debris.velocity * dt).extend(0.0);
+        t.rotate_z(debris.angular_velocity * dt);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn remove_debris_below_camera(
+    mut commands: Commands,
+    debris_q: Query<(Entity, &Transform), (With<Debris>, Without<MainCamera>)>,
+    camera_q: Query<&Transform, (With<MainCamera>, Without<Debris>)>,
+) {
+    let camera_transform = camera_q.single();
+
+    for (entity, transform) in debris_q.iter() {
+        if transform.translation.y < camera_transform.translation.y - 500.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
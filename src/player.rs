@@ -1,87 +1,291 @@
-use bevy::prelude::*;
-use crate::platforms::*;
-
-#[derive(Component)]
-pub struct Player;
-
-#[derive(Component)]
-pub struct VelocityInY {
-    pub y: f32,
-}
-
-pub fn gravity_player(
-    mut transform_query: Query<(&mut Transform, &mut VelocityInY), With<Player>>,
-time: Res<Time>) {
-    let gravity = -300.0;
-
-    for (mut t, mut vel) in &mut transform_query {
-        vel.y += gravity * time.delta_secs();
-        t.translation.y += vel.y * time.delta_secs();
-    }
-}
-
-pub fn load_player(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-) {
-    commands.spawn((
-        Sprite {
-            image: asset_server.load("images/players/player1.png"),
-            ..default()
-        },
-        Transform::from_xyz(0.0, 200.0, 0.5),
-        Player,
-        VelocityInY { y: 0.0 },
-    ));
-}
-
-pub fn player_control_in_x(key_code: Res<ButtonInput<KeyCode>>,
-            mut query: Query<(&mut Transform, &mut Sprite), With<Player>>,
-) {
-    for (mut t, mut tex) in query.iter_mut() {
-        if key_code.just_pressed(KeyCode::ArrowLeft) || key_code.just_pressed(KeyCode::KeyA) {
-            t.translation.x -= 30.0;
-            tex.flip_x = true;
-        }
-        if key_code.just_pressed(KeyCode::ArrowRight) || key_code.just_pressed(KeyCode::KeyD) {
-            t.translation.x += 30.0;
-            tex.flip_x = false;
-        }
-    }
-}
-
-pub fn collision_player_with_platforms (
-    mut player_q: Query<(&Transform, &mut VelocityInY), With<Player>>,
-    platform_q: Query<&Transform, (With<Platform>, Without<BrownPlatform>)>,
-    mut commands: Commands, asset_server: Res<AssetServer>
-) {
-    for (player, mut vel_y) in player_q.iter_mut() {
-        for platform in platform_q.iter() {
-            let collision = player.translation.y > platform.translation.y
-                && player.translation.y - 50.0 < platform.translation.y
-                && (player.translation.x - platform.translation.x).abs() < 50.0;
-
-            if collision && vel_y.y <= 0.0 {
-                vel_y.y = 800.0;
-                commands.spawn((
-                    AudioPlayer::new(asset_server.load("music/jump.mp3")),
-                    PlaybackSettings::ONCE,
-                ));
-            }
-        }
-    }
-}
-
-pub fn borders(
-    mut query: Query<&mut Transform, With<Player>>,
-) {
-    for mut t in query.iter_mut() {
-        if t.translation.x > 210.0 {
-            t.translation.x = 210.0;
-        }
-        if t.translation.x < -210.0 {
-            t.translation.x = -210.0;
-        }
-    }
-}
- 
\ No newline at end of file
+use bevy::prelude::*;
+use crate::platforms::*;
+use crate::powerups::ActivePowerUp;
+use crate::projectiles::Shooter;
+use crate::replay::ReplayPlayback;
+use crate::save::SaveData;
+use crate::skins::{active_skin_id, PlayerSkin, SkinRegistry};
+use crate::animation::PlayerAnimation;
+use crate::collision::{Collider, Landed, PreviousFeet};
+use crate::events::{BounceSurface, PlayerBounced};
+use crate::WINDOW_WIDTH;
+
+#[derive(Component)]
+pub struct Player;
+
+#[derive(Component)]
+pub struct VelocityInY {
+    pub y: f32,
+}
+
+#[derive(Component)]
+pub struct VelocityInX {
+    pub x: f32,
+}
+
+// Малюнок гравця. Анімація масштабує та повертає лише його, а не саму сутність гравця,
+// щоб копія біля протилежного краю та спрайт бонусу не успадковували ці перетворення
+#[derive(Component)]
+pub struct PlayerBody;
+
+#[derive(Component)]
+pub struct PlayerGhost;
+
+// Керування на один фіксований крок: з клавіатури під час гри або з повтору під час перегляду
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq)]
+pub struct PlayerInput {
+    pub left: bool,
+    pub right: bool,
+    pub shoot: bool,
+}
+
+impl PlayerInput {
+    pub fn to_bits(self) -> u8 {
+        self.left as u8 | (self.right as u8) << 1 | (self.shoot as u8) << 2
+    }
+
+    pub fn from_bits(bits: u8) -> Self {
+        Self {
+            left: bits & 1 != 0,
+            right: bits & 2 != 0,
+            shoot: bits & 4 != 0,
+        }
+    }
+}
+
+// Натискання пострілу між фіксованими кроками, щоб жоден не загубився
+#[derive(Resource, Default)]
+pub struct ShootLatch(pub bool);
+
+pub const PLAYER_HALF_WIDTH: f32 = 50.0;
+pub const PLAYER_HALF_HEIGHT: f32 = 45.0;
+// Тіло дудла без носа: саме ним він стає на платформи
+pub const PLAYER_BODY_HALF_WIDTH: f32 = 30.0;
+pub const PLAYER_START_Y: f32 = 200.0;
+pub const GRAVITY: f32 = -300.0;
+pub const JUMP_VELOCITY: f32 = 800.0;
+pub const ACCELERATION_IN_X: f32 = 1800.0;
+pub const MAX_SPEED_IN_X: f32 = 400.0;
+pub const AIR_FRICTION_IN_X: f32 = 1200.0;
+
+pub fn gravity_player(
+    mut transform_query: Query<(&mut Transform, &mut VelocityInY, Option<&ActivePowerUp>), With<Player>>,
+time: Res<Time>) {
+    for (mut t, mut vel, active) in &mut transform_query {
+        // Під час польоту предмет повністю замінює гравітацію
+        match active.and_then(|active| active.kind.flight()) {
+            Some((_, flight_velocity)) => vel.y = flight_velocity,
+            None => vel.y += GRAVITY * time.delta_secs(),
+        }
+        t.translation.y += vel.y * time.delta_secs();
+    }
+}
+
+pub fn max_jump_height() -> f32 {
+    JUMP_VELOCITY * JUMP_VELOCITY / (2.0 * -GRAVITY)
+}
+
+// Горизонтальна відстань, яку гравець пролітає, поки піднімається вище за dy і падає назад на цю висоту
+pub fn max_horizontal_reach(dy: f32) -> f32 {
+    let discriminant = JUMP_VELOCITY * JUMP_VELOCITY + 2.0 * GRAVITY * dy;
+    if discriminant < 0.0 {
+        return 0.0;
+    }
+
+    let air_time = (JUMP_VELOCITY + discriminant.sqrt()) / -GRAVITY;
+
+    // Гравець стартує з місця й розганяється до MAX_SPEED_IN_X, а не летить одразу на максимальній швидкості
+    let ramp_time = MAX_SPEED_IN_X / ACCELERATION_IN_X;
+    if air_time <= ramp_time {
+        0.5 * ACCELERATION_IN_X * air_time * air_time
+    } else {
+        MAX_SPEED_IN_X * (air_time - 0.5 * ramp_time)
+    }
+}
+
+pub fn load_player(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    registry: Res<SkinRegistry>,
+    save: Res<SaveData>,
+    playback: Res<ReplayPlayback>,
+) {
+    let skin = registry.get(active_skin_id(&save, &playback));
+    let sprite = skin.sprite(&asset_server);
+
+    commands.spawn((
+        Transform::from_xyz(0.0, PLAYER_START_Y, 0.5),
+        Visibility::default(),
+        Player,
+        VelocityInY { y: 0.0 },
+        VelocityInX { x: 0.0 },
+        Shooter::default(),
+        PlayerSkin::new(skin, &asset_server),
+        Collider::new(Vec2::new(skin.half_size.0, skin.half_size.1)),
+        PreviousFeet { y: PLAYER_START_Y - skin.half_size.1 },
+        PlayerAnimation::default(),
+    )).with_children(|parent| {
+        parent.spawn((
+            sprite.clone(),
+            Transform::default(),
+            PlayerBody,
+        ));
+        // Копія гравця з протилежного боку екрана, поки він перетинає край
+        parent.spawn((
+            sprite,
+            Transform::default(),
+            Visibility::Hidden,
+            PlayerGhost,
+        ));
+    });
+}
+
+pub fn latch_shoot_input(
+    key_code: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut latch: ResMut<ShootLatch>,
+) {
+    if key_code.just_pressed(KeyCode::Space) || mouse.just_pressed(MouseButton::Left) {
+        latch.0 = true;
+    }
+}
+
+pub fn read_keyboard_input(
+    key_code: Res<ButtonInput<KeyCode>>,
+    mut latch: ResMut<ShootLatch>,
+    mut input: ResMut<PlayerInput>,
+) {
+    *input = PlayerInput {
+        left: key_code.pressed(KeyCode::ArrowLeft) || key_code.pressed(KeyCode::KeyA),
+        right: key_code.pressed(KeyCode::ArrowRight) || key_code.pressed(KeyCode::KeyD),
+        shoot: std::mem::take(&mut latch.0),
+    };
+}
+
+pub fn player_control_in_x(input: Res<PlayerInput>,
+            mut query: Query<(&mut Transform, &mut VelocityInX, &PlayerSkin, &Children), With<Player>>,
+            mut body_q: Query<&mut Sprite, With<PlayerBody>>,
+            time: Res<Time>,
+) {
+    let left = input.left;
+    let right = input.right;
+    let dt = time.delta_secs();
+
+    for (mut t, mut vel, skin, children) in query.iter_mut() {
+        let direction = match (left, right) {
+            (true, false) => -1.0,
+            (false, true) => 1.0,
+            _ => 0.0,
+        };
+
+        if direction != 0.0 {
+            vel.x += direction * ACCELERATION_IN_X * dt;
+            let mut bodies = body_q.iter_many_mut(children);
+            while let Some(mut tex) = bodies.fetch_next() {
+                tex.flip_x = (direction < 0.0) != skin.faces_left;
+            }
+        } else {
+            // Тертя повітря гасить швидкість до нуля, але не змінює напрямок
+            let friction = AIR_FRICTION_IN_X * dt;
+            vel.x = if vel.x.abs() <= friction { 0.0 } else { vel.x - friction * vel.x.signum() };
+        }
+
+        vel.x = vel.x.clamp(-MAX_SPEED_IN_X, MAX_SPEED_IN_X);
+        t.translation.x += vel.x * dt;
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn bounce_on_platforms(
+    mut landed: EventReader<Landed>,
+    mut player_q: Query<(&mut VelocityInY, &mut VelocityInX), With<Player>>,
+    platform_q: Query<(Option<&MovingPlatform>, Has<VanishingPlatform>), (With<Platform>, Without<BrownPlatform>)>,
+    mut commands: Commands,
+    mut bounced: EventWriter<PlayerBounced>,
+) {
+    for event in landed.read() {
+        let Ok((moving, vanishing)) = platform_q.get(event.surface) else { continue };
+        let Ok((mut vel_y, mut vel_x)) = player_q.get_mut(event.player) else { continue };
+
+        vel_y.y = JUMP_VELOCITY;
+        // Рухома платформа передає гравцю свою швидкість
+        if let Some(moving) = moving {
+            vel_x.x = (vel_x.x + moving.velocity.x).clamp(-MAX_SPEED_IN_X, MAX_SPEED_IN_X);
+            vel_y.y += moving.velocity.y.max(0.0);
+        }
+        if vanishing {
+            commands.entity(event.surface).despawn();
+        }
+        bounced.send(PlayerBounced {
+            player: event.player,
+            surface_kind: BounceSurface::Platform,
+        });
+    }
+}
+
+pub fn play_jump_sound(
+    mut bounced: EventReader<PlayerBounced>,
+    player_q: Query<&PlayerSkin, With<Player>>,
+    mut commands: Commands,
+) {
+    for event in bounced.read() {
+        if let Ok(skin) = player_q.get(event.player) {
+            commands.spawn((
+                AudioPlayer::new(skin.jump_sound.clone()),
+                PlaybackSettings::ONCE,
+            ));
+        }
+    }
+}
+
+pub fn borders(
+    mut query: Query<&mut Transform, With<Player>>,
+) {
+    let half_width = WINDOW_WIDTH / 2.0;
+
+    for mut t in query.iter_mut() {
+        if t.translation.x > half_width {
+            t.translation.x -= WINDOW_WIDTH;
+        }
+        if t.translation.x < -half_width {
+            t.translation.x += WINDOW_WIDTH;
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn update_player_ghost(
+    player_q: Query<(&Transform, &Children), With<Player>>,
+    body_q: Query<(&Transform, &Sprite), (With<PlayerBody>, Without<PlayerGhost>)>,
+    mut ghost_q: Query<(&mut Transform, &mut Sprite, &mut Visibility),
+        (With<PlayerGhost>, Without<Player>, Without<PlayerBody>)>,
+) {
+    // Копія для перенесення через край — це малюнок, а не тіло: вона має з'явитися, щойно за край
+    // вийде будь-яка частина спрайта разом із носом, тому межа рахується від PLAYER_HALF_WIDTH, а не від Collider
+    let edge = WINDOW_WIDTH / 2.0 - PLAYER_HALF_WIDTH;
+
+    for (player, children) in player_q.iter() {
+        let Some((body, body_sprite)) = body_q.iter_many(children).next() else { continue };
+
+        for &child in children.iter() {
+            let Ok((mut t, mut sprite, mut visibility)) = ghost_q.get_mut(child) else { continue };
+
+            // Копія повторює анімацію малюнка, але зсувається лише по x
+            t.scale = body.scale;
+            t.rotation = body.rotation;
+            sprite.flip_x = body_sprite.flip_x;
+            if let (Some(atlas), Some(body_atlas)) = (&mut sprite.texture_atlas, &body_sprite.texture_atlas) {
+                atlas.index = body_atlas.index;
+            }
+            if player.translation.x > edge {
+                t.translation.x = -WINDOW_WIDTH;
+                *visibility = Visibility::Inherited;
+            } else if player.translation.x < -edge {
+                t.translation.x = WINDOW_WIDTH;
+                *visibility = Visibility::Inherited;
+            } else {
+                *visibility = Visibility::Hidden;
+            }
+        }
+    }
+}
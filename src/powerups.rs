@@ -0,0 +1,222 @@
+use bevy::prelude::*;
+use rand::Rng;
+use crate::player::*;
+use crate::level::LevelRng;
+use crate::collision::{Collider, Landable, Landed, PreviousTop, Touched};
+use crate::events::{BounceSurface, PlayerBounced, PowerUpCollected};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PowerUpKind {
+    Spring,
+    Trampoline,
+    PropellerHat,
+    Jetpack,
+}
+
+// Імовірність появи предмета на звичайній платформі
+pub const POWER_UP_TABLE: &[(PowerUpKind, f64)] = &[
+    (PowerUpKind::Spring, 0.06),
+    (PowerUpKind::Trampoline, 0.02),
+    (PowerUpKind::PropellerHat, 0.01),
+    (PowerUpKind::Jetpack, 0.005),
+];
+
+impl PowerUpKind {
+    pub fn size(self) -> Vec2 {
+        match self {
+            PowerUpKind::Spring => Vec2::new(16.0, 12.0),
+            PowerUpKind::Trampoline => Vec2::new(36.0, 8.0),
+            PowerUpKind::PropellerHat => Vec2::new(26.0, 14.0),
+            PowerUpKind::Jetpack => Vec2::new(20.0, 30.0),
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            PowerUpKind::Spring => Color::srgb(0.55, 0.55, 0.6),
+            PowerUpKind::Trampoline => Color::srgb(0.2, 0.4, 0.9),
+            PowerUpKind::PropellerHat => Color::srgb(0.95, 0.8, 0.1),
+            PowerUpKind::Jetpack => Color::srgb(0.8, 0.2, 0.15),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PowerUpKind::Spring => "Пружина",
+            PowerUpKind::Trampoline => "Батут",
+            PowerUpKind::PropellerHat => "Пропелер",
+            PowerUpKind::Jetpack => "Реактивний ранець",
+        }
+    }
+
+    // Швидкість відскоку для предметів, на які стрибають
+    pub fn bounce_velocity(self) -> Option<f32> {
+        match self {
+            PowerUpKind::Spring => Some(1300.0),
+            PowerUpKind::Trampoline => Some(1700.0),
+            _ => None,
+        }
+    }
+
+    // Тривалість і швидкість польоту для предметів, які підбирають
+    pub fn flight(self) -> Option<(f32, f32)> {
+        match self {
+            PowerUpKind::PropellerHat => Some((3.0, 700.0)),
+            PowerUpKind::Jetpack => Some((4.0, 1300.0)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct PowerUp {
+    pub kind: PowerUpKind,
+}
+
+#[derive(Component)]
+pub struct ActivePowerUp {
+    pub kind: PowerUpKind,
+    pub timer: Timer,
+}
+
+#[derive(Component)]
+pub struct ActivePowerUpSprite;
+
+#[derive(Component)]
+pub struct PowerUpText;
+
+pub fn maybe_spawn_power_up(commands: &mut Commands, level_rng: &mut LevelRng, platform: Entity) {
+    let mut roll = level_rng.rng.gen::<f64>();
+    let mut picked = None;
+
+    for &(kind, chance) in POWER_UP_TABLE {
+        if roll < chance {
+            picked = Some(kind);
+            break;
+        }
+        roll -= chance;
+    }
+
+    let Some(kind) = picked else { return };
+    let size = kind.size();
+    let x = level_rng.rng.gen_range(-12.0..12.0);
+
+    commands.entity(platform).with_children(|parent| {
+        let mut power_up = parent.spawn((
+            PowerUp { kind },
+            Sprite::from_color(kind.color(), size),
+            Transform::from_xyz(x, 7.0 + size.y / 2.0, 0.1),
+            Collider::from_size(size),
+        ));
+        // На пружину й батут стають зверху, а предмети для польоту підбирають дотиком
+        if kind.bounce_velocity().is_some() {
+            power_up.insert((Landable, PreviousTop::default()));
+        }
+    });
+}
+
+pub fn bounce_on_power_ups(
+    mut landed: EventReader<Landed>,
+    mut player_q: Query<&mut VelocityInY, With<Player>>,
+    power_up_q: Query<&PowerUp>,
+    mut bounced: EventWriter<PlayerBounced>,
+    mut collected: EventWriter<PowerUpCollected>,
+) {
+    for event in landed.read() {
+        let Ok(power_up) = power_up_q.get(event.surface) else { continue };
+        let Some(bounce) = power_up.kind.bounce_velocity() else { continue };
+        let Ok(mut vel_y) = player_q.get_mut(event.player) else { continue };
+
+        vel_y.y = bounce;
+        collected.send(PowerUpCollected);
+        bounced.send(PlayerBounced {
+            player: event.player,
+            surface_kind: BounceSurface::PowerUp,
+        });
+    }
+}
+
+pub fn pick_up_power_ups(
+    mut commands: Commands,
+    mut touched: EventReader<Touched>,
+    player_q: Query<(&Collider, Has<ActivePowerUp>), With<Player>>,
+    power_up_q: Query<&PowerUp>,
+    mut collected: EventWriter<PowerUpCollected>,
+) {
+    for event in touched.read() {
+        let Ok(power_up) = power_up_q.get(event.other) else { continue };
+        let Some((duration, _)) = power_up.kind.flight() else { continue };
+        let Ok((body, flying)) = player_q.get(event.player) else { continue };
+
+        if flying {
+            continue;
+        }
+
+        collected.send(PowerUpCollected);
+        commands.entity(event.other).despawn_recursive();
+        commands.entity(event.player).insert(ActivePowerUp {
+            kind: power_up.kind,
+            timer: Timer::from_seconds(duration, TimerMode::Once),
+        }).with_children(|parent| {
+            parent.spawn((
+                ActivePowerUpSprite,
+                Sprite::from_color(power_up.kind.color(), power_up.kind.size()),
+                Transform::from_xyz(0.0, body.half_size.y, 0.1),
+            ));
+        });
+    }
+}
+
+pub fn tick_active_power_ups(
+    mut commands: Commands,
+    mut player_q: Query<(Entity, &mut ActivePowerUp, &Children), With<Player>>,
+    sprite_q: Query<Entity, With<ActivePowerUpSprite>>,
+    time: Res<Time>,
+) {
+    for (entity, mut active, children) in player_q.iter_mut() {
+        active.timer.tick(time.delta());
+        if active.timer.finished() {
+            commands.entity(entity).remove::<ActivePowerUp>();
+            for &child in children.iter() {
+                if sprite_q.contains(child) {
+                    commands.entity(child).despawn_recursive();
+                }
+            }
+        }
+    }
+}
+
+pub fn setup_power_up_text(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        PowerUpText,
+        Text::new(""),
+        TextFont {
+            font: asset_server.load("fonts/Arsenal-Regular.ttf"),
+            font_size: 24.0,
+            ..default()
+        },
+        TextColor(Color::BLACK),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(45.0),
+            left: Val::Px(10.0),
+            ..default()
+        }
+    ));
+}
+
+pub fn update_power_up_text(
+    player_q: Query<&ActivePowerUp, With<Player>>,
+    mut text_q: Query<&mut Text, With<PowerUpText>>,
+) {
+    let label = match player_q.get_single() {
+        Ok(active) => format!("{}: {:.1} с", active.kind.name(), active.timer.remaining_secs()),
+        Err(_) => String::new(),
+    };
+
+    for mut text in text_q.iter_mut() {
+        if **text != label {
+            **text = label.clone();
+        }
+    }
+}
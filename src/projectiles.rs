@@ -0,0 +1,129 @@
+use std::collections::HashSet;
+use bevy::prelude::*;
+use crate::camera::MainCamera;
+use crate::hazards::{BlackHole, Monster, BLACK_HOLE_CORE_RADIUS};
+use crate::player::*;
+use crate::collision::Collider;
+use crate::events::MonsterKilled;
+use crate::WINDOW_HEIGHT;
+
+#[derive(Component)]
+pub struct Projectile {
+    pub velocity: f32,
+}
+
+#[derive(Component)]
+pub struct Shooter {
+    pub cooldown: Timer,
+    pub pose: Timer,
+}
+
+impl Default for Shooter {
+    fn default() -> Self {
+        let mut cooldown = Timer::from_seconds(SHOOT_COOLDOWN_SECS, TimerMode::Once);
+        let mut pose = Timer::from_seconds(SHOOT_POSE_SECS, TimerMode::Once);
+        // Перший постріл доступний одразу
+        cooldown.tick(cooldown.duration());
+        pose.tick(pose.duration());
+        Self { cooldown, pose }
+    }
+}
+
+pub const SHOOT_COOLDOWN_SECS: f32 = 0.25;
+pub const SHOOT_POSE_SECS: f32 = 0.2;
+pub const PROJECTILE_SPEED: f32 = 1000.0;
+pub const PROJECTILE_SIZE: f32 = 10.0;
+
+pub fn shoot(
+    mut commands: Commands,
+    input: Res<PlayerInput>,
+    mut player_q: Query<(&Transform, &mut Shooter, &Collider), With<Player>>,
+    time: Res<Time>,
+) {
+    let pressed = input.shoot;
+
+    for (player, mut shooter, body) in player_q.iter_mut() {
+        shooter.cooldown.tick(time.delta());
+        shooter.pose.tick(time.delta());
+
+        if !pressed || !shooter.cooldown.finished() {
+            continue;
+        }
+
+        shooter.cooldown.reset();
+        shooter.pose.reset();
+        commands.spawn((
+            Projectile { velocity: PROJECTILE_SPEED },
+            Sprite::from_color(Color::srgb(0.15, 0.15, 0.15), Vec2::splat(PROJECTILE_SIZE)),
+            Collider::from_size(Vec2::splat(PROJECTILE_SIZE)),
+            Transform::from_xyz(player.translation.x, player.translation.y + body.half_size.y, 0.6),
+        ));
+    }
+}
+
+pub fn move_projectiles(
+    mut q: Query<(&mut Transform, &Projectile)>,
+    time: Res<Time>,
+) {
+    for (mut t, projectile) in q.iter_mut() {
+        t.translation.y += projectile.velocity * time.delta_secs();
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn collision_projectiles_with_hazards(
+    mut commands: Commands,
+    projectile_q: Query<(Entity, &Transform, &Collider), With<Projectile>>,
+    monster_q: Query<(Entity, &Transform, &Collider), (With<Monster>, Without<Projectile>)>,
+    black_hole_q: Query<&Transform, (With<BlackHole>, Without<Projectile>)>,
+    mut killed: EventWriter<MonsterKilled>,
+) {
+    // Кожен снаряд і кожен монстр беруть участь щонайбільше в одному влучанні за крок
+    let mut hit_monsters = HashSet::new();
+
+    'projectiles: for (projectile_entity, projectile, projectile_collider) in projectile_q.iter() {
+        for (monster_entity, monster, monster_collider) in monster_q.iter() {
+            if hit_monsters.contains(&monster_entity) {
+                continue;
+            }
+
+            let hit = projectile_collider.overlaps(
+                projectile.translation.truncate(),
+                monster_collider,
+                monster.translation.truncate(),
+            );
+
+            if hit {
+                hit_monsters.insert(monster_entity);
+                killed.send(MonsterKilled);
+                commands.entity(monster_entity).despawn();
+                commands.entity(projectile_entity).despawn();
+                continue 'projectiles;
+            }
+        }
+
+        // Чорна діра поглинає снаряди
+        for black_hole in black_hole_q.iter() {
+            if projectile.translation.truncate().distance(black_hole.translation.truncate())
+                < BLACK_HOLE_CORE_RADIUS {
+                commands.entity(projectile_entity).despawn();
+                continue 'projectiles;
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn remove_projectiles_off_camera(
+    mut commands: Commands,
+    projectile_q: Query<(Entity, &Transform), (With<Projectile>, Without<MainCamera>)>,
+    camera_q: Query<&Transform, (With<MainCamera>, Without<Projectile>)>,
+) {
+    let camera_transform = camera_q.single();
+
+    for (entity, transform) in projectile_q.iter() {
+        if transform.translation.y > camera_transform.translation.y + WINDOW_HEIGHT / 2.0 + PROJECTILE_SIZE {
+            commands.entity(entity).despawn();
+        }
+    }
+}
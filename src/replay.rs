@@ -0,0 +1,519 @@
+use std::path::PathBuf;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use crate::game::{GameState, PhysicsSet};
+use crate::level::LevelRng;
+use crate::persistence::WriteFileRequested;
+use crate::player::*;
+use crate::save::{save_dir, SaveData};
+
+const REPLAY_MAGIC: &[u8; 4] = b"DJRP";
+// Версія змінюється разом із правилами фізики: старий запис на нових правилах розійдеться з оригіналом
+const REPLAY_VERSION: u8 = 5;
+pub const REPLAY_TICK_RATE: u16 = 60;
+pub const REPLAY_SEEK_TICKS: usize = 5 * REPLAY_TICK_RATE as usize;
+pub const REPLAY_SEEK_SPEED: f32 = 8.0;
+pub const REPLAY_SPEEDS: [(KeyCode, f32); 4] = [
+    (KeyCode::Digit1, 0.5),
+    (KeyCode::Digit2, 1.0),
+    (KeyCode::Digit3, 2.0),
+    (KeyCode::Digit4, 4.0),
+];
+const SCRUBBER_HEIGHT: f32 = 40.0;
+
+// Сід рівня, персонаж та керування на кожен фіксований крок
+#[derive(Clone, Debug, Default)]
+pub struct Replay {
+    pub seed: u64,
+    pub tick_rate: u16,
+    pub skin: String,
+    pub inputs: Vec<u8>,
+}
+
+impl Replay {
+    // Формат: "DJRP", версія (u8), сід (u64), частота кроків (u16), кількість кроків (u32),
+    // персонаж (довжина u8 і байти UTF-8), далі пари (довжина серії u16, біти керування u8).
+    // Усі числа little-endian.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(19 + self.inputs.len() / 8);
+        bytes.extend_from_slice(REPLAY_MAGIC);
+        bytes.push(REPLAY_VERSION);
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&self.tick_rate.to_le_bytes());
+        bytes.extend_from_slice(&(self.inputs.len() as u32).to_le_bytes());
+        let skin = &self.skin.as_bytes()[..self.skin.len().min(u8::MAX as usize)];
+        bytes.push(skin.len() as u8);
+        bytes.extend_from_slice(skin);
+
+        let mut iter = self.inputs.iter().peekable();
+        while let Some(&bits) = iter.next() {
+            let mut run: u16 = 1;
+            while run < u16::MAX && iter.peek() == Some(&&bits) {
+                iter.next();
+                run += 1;
+            }
+            bytes.extend_from_slice(&run.to_le_bytes());
+            bytes.push(bits);
+        }
+
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 19 || &bytes[..4] != REPLAY_MAGIC {
+            return Err("це не файл повтору".to_string());
+        }
+        if bytes[4] != REPLAY_VERSION {
+            return Err(format!("повтор записано іншою версією гри ({})", bytes[4]));
+        }
+
+        let seed = u64::from_le_bytes(bytes[5..13].try_into().unwrap_or_default());
+        let tick_rate = u16::from_le_bytes(bytes[13..15].try_into().unwrap_or_default());
+        if tick_rate != REPLAY_TICK_RATE {
+            return Err(format!("повтор записано з частотою {} кроків на секунду замість {}", tick_rate, REPLAY_TICK_RATE));
+        }
+        let ticks = u32::from_le_bytes(bytes[15..19].try_into().unwrap_or_default()) as usize;
+
+        let len = bytes.get(19).copied().ok_or("обірваний файл повтору")? as usize;
+        let skin = bytes.get(20..20 + len).ok_or("обірваний файл повтору")?;
+        let skin = String::from_utf8(skin.to_vec()).map_err(|_| "некоректна назва персонажа")?;
+        let runs = &bytes[20 + len..];
+
+        // Кількість кроків із заголовка не використовується для виділення пам'яті:
+        // пошкоджений файл не повинен змусити гру просити гігабайти
+        let mut inputs = Vec::new();
+        for chunk in runs.chunks(3) {
+            let [low, high, bits] = chunk else { return Err("обірваний файл повтору".to_string()) };
+            let run = u16::from_le_bytes([*low, *high]) as usize;
+            if inputs.len() + run > ticks {
+                return Err(format!("файл повтору містить більше за {} кроків", ticks));
+            }
+            inputs.extend(std::iter::repeat_n(*bits, run));
+        }
+
+        if inputs.len() != ticks {
+            return Err(format!("очікувалося {} кроків, прочитано {}", ticks, inputs.len()));
+        }
+
+        Ok(Self { seed, tick_rate, skin, inputs })
+    }
+
+    pub fn duration_secs(&self) -> f32 {
+        self.inputs.len() as f32 / self.tick_rate.max(1) as f32
+    }
+}
+
+pub fn replay_dir() -> PathBuf {
+    save_dir().join("replays")
+}
+
+pub fn last_replay_path() -> PathBuf {
+    replay_dir().join("last.djr")
+}
+
+fn replay_path_from_args() -> Option<PathBuf> {
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        if let Some(value) = arg.strip_prefix("--replay=") {
+            return Some(PathBuf::from(value));
+        }
+        if arg == "--replay" {
+            return args.next().map(PathBuf::from);
+        }
+    }
+
+    None
+}
+
+#[derive(Resource, Default)]
+pub struct ReplayRecorder {
+    pub inputs: Vec<u8>,
+}
+
+// Повтор, який можна переглянути зі стартового екрана
+#[derive(Resource, Default)]
+pub struct LastReplay(pub Option<Replay>);
+
+#[derive(Resource, Default)]
+pub struct ReplayPlayback {
+    pub replay: Option<Replay>,
+    pub cursor: usize,
+    pub speed: f32,
+    pub seek_target: Option<usize>,
+    // Перемотування назад перезапускає рівень і проганяє його до потрібного кроку
+    pub restarting: bool,
+    // Сід гравця до початку перегляду, щоб заданий через --seed не загубився
+    pub saved_seed: u64,
+}
+
+impl ReplayPlayback {
+    pub fn is_active(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.replay.as_ref().map_or(0, |replay| replay.inputs.len())
+    }
+}
+
+#[derive(Component)]
+pub struct ReplayHud;
+
+#[derive(Component)]
+pub struct ReplayHudText;
+
+#[derive(Component)]
+pub struct ReplayHudBar;
+
+pub fn replaying(playback: Res<ReplayPlayback>) -> bool {
+    playback.is_active()
+}
+
+pub fn not_replaying(playback: Res<ReplayPlayback>) -> bool {
+    !playback.is_active()
+}
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        let path = replay_path_from_args().unwrap_or_else(last_replay_path);
+        let last_replay = match std::fs::read(&path) {
+            Ok(bytes) => match Replay::decode(&bytes) {
+                Ok(replay) => Some(replay),
+                Err(error) => {
+                    warn!("Не вдалося прочитати повтор {:?}: {}", path, error);
+                    None
+                }
+            },
+            Err(_) => None,
+        };
+
+        app.insert_resource(LastReplay(last_replay))
+            .init_resource::<ReplayRecorder>()
+            .init_resource::<ReplayPlayback>()
+            .add_systems(FixedUpdate, (
+                read_keyboard_input.run_if(not_replaying),
+                feed_replay_input.run_if(replaying),
+                record_input.run_if(not_replaying),
+                )
+                .chain()
+                .in_set(PhysicsSet::ReadInput))
+            .add_systems(OnEnter(GameState::InProcessGame), (start_recording, spawn_replay_hud))
+            .add_systems(OnExit(GameState::InProcessGame), clean_replay_hud)
+            .add_systems(OnEnter(GameState::GameOver), finish_recording.run_if(not_replaying))
+            .add_systems(OnExit(GameState::GameOver), stop_playback)
+            .add_systems(Update, (start_replay, resume_replay_after_seek)
+                .run_if(in_state(GameState::NotStarted)))
+            .add_systems(Update, (replay_controls, update_replay_hud)
+                .run_if(in_state(GameState::InProcessGame))
+                .run_if(replaying))
+            .add_systems(Update, apply_replay_speed);
+    }
+}
+
+fn record_input(input: Res<PlayerInput>, mut recorder: ResMut<ReplayRecorder>) {
+    recorder.inputs.push(input.to_bits());
+}
+
+fn feed_replay_input(mut playback: ResMut<ReplayPlayback>, mut input: ResMut<PlayerInput>) {
+    let cursor = playback.cursor;
+    let bits = playback.replay.as_ref()
+        .and_then(|replay| replay.inputs.get(cursor).copied())
+        .unwrap_or(0);
+
+    *input = PlayerInput::from_bits(bits);
+    playback.cursor += 1;
+}
+
+fn start_recording(mut recorder: ResMut<ReplayRecorder>, playback: Res<ReplayPlayback>) {
+    if !playback.is_active() {
+        recorder.inputs.clear();
+    }
+}
+
+fn finish_recording(
+    mut recorder: ResMut<ReplayRecorder>,
+    mut last_replay: ResMut<LastReplay>,
+    mut writes: EventWriter<WriteFileRequested>,
+    level_rng: Res<LevelRng>,
+    save: Res<SaveData>,
+) {
+    let replay = Replay {
+        seed: level_rng.seed,
+        tick_rate: REPLAY_TICK_RATE,
+        skin: save.settings.skin.clone(),
+        inputs: std::mem::take(&mut recorder.inputs),
+    };
+    writes.send(WriteFileRequested { path: last_replay_path(), bytes: replay.encode() });
+    last_replay.0 = Some(replay);
+}
+
+fn begin_playback(playback: &mut ReplayPlayback, level_rng: &mut LevelRng) {
+    if let Some(replay) = &playback.replay {
+        level_rng.seed = replay.seed;
+        level_rng.reset();
+    }
+    playback.cursor = 0;
+    playback.restarting = false;
+}
+
+fn start_replay(
+    key_code: Res<ButtonInput<KeyCode>>,
+    last_replay: Res<LastReplay>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut level_rng: ResMut<LevelRng>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !key_code.just_pressed(KeyCode::KeyV) || playback.is_active() {
+        return;
+    }
+    let Some(replay) = &last_replay.0 else { return };
+
+    playback.replay = Some(replay.clone());
+    playback.saved_seed = level_rng.seed;
+    playback.speed = 1.0;
+    playback.seek_target = None;
+    begin_playback(&mut playback, &mut level_rng);
+    next_state.set(GameState::InProcessGame);
+}
+
+fn resume_replay_after_seek(
+    mut playback: ResMut<ReplayPlayback>,
+    mut level_rng: ResMut<LevelRng>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if playback.restarting {
+        begin_playback(&mut playback, &mut level_rng);
+        next_state.set(GameState::InProcessGame);
+    }
+}
+
+fn stop_playback(mut playback: ResMut<ReplayPlayback>, mut level_rng: ResMut<LevelRng>) {
+    if !playback.restarting {
+        level_rng.seed = playback.saved_seed;
+        level_rng.reset();
+        *playback = ReplayPlayback::default();
+    }
+}
+
+fn replay_controls(
+    key_code: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    window_q: Query<&Window, With<PrimaryWindow>>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (key, speed) in REPLAY_SPEEDS {
+        if key_code.just_pressed(key) {
+            playback.speed = speed;
+        }
+    }
+
+    let len = playback.len();
+    let mut target = None;
+    if key_code.just_pressed(KeyCode::ArrowRight) {
+        target = Some((playback.cursor + REPLAY_SEEK_TICKS).min(len));
+    }
+    if key_code.just_pressed(KeyCode::ArrowLeft) {
+        target = Some(playback.cursor.saturating_sub(REPLAY_SEEK_TICKS));
+    }
+
+    // Клік по смузі внизу екрана перемотує до відповідного місця
+    if mouse.just_pressed(MouseButton::Left) {
+        if let Ok(window) = window_q.get_single() {
+            if let Some(position) = window.cursor_position() {
+                if position.y > window.height() - SCRUBBER_HEIGHT {
+                    let fraction = (position.x / window.width()).clamp(0.0, 1.0);
+                    target = Some((fraction * len as f32) as usize);
+                }
+            }
+        }
+    }
+
+    let Some(target) = target else { return };
+    playback.seek_target = Some(target);
+    if target < playback.cursor {
+        playback.restarting = true;
+        next_state.set(GameState::NotStarted);
+    }
+}
+
+fn apply_replay_speed(mut playback: ResMut<ReplayPlayback>, mut time: ResMut<Time<Virtual>>) {
+    if playback.seek_target.is_some_and(|target| playback.cursor >= target) {
+        playback.seek_target = None;
+    }
+
+    let speed = if !playback.is_active() {
+        1.0
+    } else if playback.seek_target.is_some() {
+        REPLAY_SEEK_SPEED
+    } else {
+        playback.speed
+    };
+
+    if time.relative_speed() != speed {
+        time.set_relative_speed(speed);
+    }
+}
+
+fn spawn_replay_hud(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    playback: Res<ReplayPlayback>,
+) {
+    if !playback.is_active() {
+        return;
+    }
+
+    commands.spawn((
+        ReplayHud,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(0.0),
+            left: Val::Px(0.0),
+            width: Val::Percent(100.0),
+            height: Val::Px(SCRUBBER_HEIGHT),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::End,
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn((
+            ReplayHudText,
+            Text::new(""),
+            TextFont {
+                font: asset_server.load("fonts/Arsenal-Regular.ttf"),
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(Color::BLACK),
+            Node {
+                margin: UiRect::left(Val::Px(10.0)),
+                ..default()
+            },
+        ));
+
+        parent.spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Px(8.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.2)),
+        )).with_children(|bar| {
+            bar.spawn((
+                ReplayHudBar,
+                Node {
+                    width: Val::Percent(0.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.2, 0.5, 0.9)),
+            ));
+        });
+    });
+}
+
+fn update_replay_hud(
+    playback: Res<ReplayPlayback>,
+    mut text_q: Query<&mut Text, With<ReplayHudText>>,
+    mut bar_q: Query<&mut Node, With<ReplayHudBar>>,
+) {
+    let len = playback.len().max(1);
+    let tick_rate = REPLAY_TICK_RATE as f32;
+    let label = format!(
+        "Повтор ×{}  {:.0} / {:.0} с   1–4 швидкість, ←/→ перемотка",
+        playback.speed,
+        playback.cursor.min(len) as f32 / tick_rate,
+        playback.replay.as_ref().map_or(0.0, Replay::duration_secs),
+    );
+
+    for mut text in text_q.iter_mut() {
+        if **text != label {
+            **text = label.clone();
+        }
+    }
+    for mut node in bar_q.iter_mut() {
+        node.width = Val::Percent(playback.cursor.min(len) as f32 / len as f32 * 100.0);
+    }
+}
+
+fn clean_replay_hud(mut commands: Commands, hud_q: Query<Entity, With<ReplayHud>>) {
+    for entity in hud_q.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Replay {
+        let mut inputs = vec![0; 10];
+        inputs.extend([1, 1, 2, 4, 4, 4, 0]);
+        // Серія довша за u16::MAX має розбитися на кілька записів
+        inputs.extend(std::iter::repeat_n(2, u16::MAX as usize + 5));
+        Replay { seed: 42, tick_rate: REPLAY_TICK_RATE, skin: "player2".to_string(), inputs }
+    }
+
+    #[test]
+    fn round_trip() {
+        let replay = sample();
+        let decoded = Replay::decode(&replay.encode()).unwrap();
+
+        assert_eq!(decoded.seed, replay.seed);
+        assert_eq!(decoded.tick_rate, replay.tick_rate);
+        assert_eq!(decoded.skin, replay.skin);
+        assert_eq!(decoded.inputs, replay.inputs);
+    }
+
+    #[test]
+    fn empty_run_round_trip() {
+        let replay = Replay { tick_rate: REPLAY_TICK_RATE, ..default() };
+        let decoded = Replay::decode(&replay.encode()).unwrap();
+
+        assert!(decoded.inputs.is_empty());
+        assert_eq!(decoded.skin, "");
+    }
+
+    #[test]
+    fn rejects_truncated_file() {
+        let bytes = sample().encode();
+
+        for len in [0, 4, 18, 20, bytes.len() - 1, bytes.len() - 3] {
+            assert!(Replay::decode(&bytes[..len]).is_err(), "довжина {}", len);
+        }
+    }
+
+    #[test]
+    fn rejects_more_runs_than_header_ticks() {
+        let mut bytes = sample().encode();
+        bytes[15..19].copy_from_slice(&5u32.to_le_bytes());
+
+        assert!(Replay::decode(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_huge_tick_count_without_allocating() {
+        let mut bytes = sample().encode();
+        bytes[15..19].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        assert!(Replay::decode(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_other_versions_and_tick_rates() {
+        let mut bytes = sample().encode();
+        bytes[4] = REPLAY_VERSION + 1;
+        assert!(Replay::decode(&bytes).is_err());
+
+        let replay = Replay { tick_rate: REPLAY_TICK_RATE * 2, ..sample() };
+        assert!(Replay::decode(&replay.encode()).is_err());
+
+        let mut bytes = sample().encode();
+        bytes[0] = b'X';
+        assert!(Replay::decode(&bytes).is_err());
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::skins::DEFAULT_SKIN;
+
+pub const SAVE_VERSION: u32 = 1;
+pub const HISTORY_LIMIT: usize = 100;
+pub const LEADERBOARD_SIZE: usize = 10;
+
+const SAVE_FILE: &str = "save.ron";
+const LEGACY_HIGH_SCORE_FILE: &str = "high_score.txt";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct Settings {
+    pub volume: f32,
+    pub skin: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            skin: DEFAULT_SKIN.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct Statistics {
+    pub games_played: u64,
+    pub total_bounces: u64,
+    pub best_altitude: f32,
+    pub deaths: BTreeMap<String, u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct RunRecord {
+    pub score: u64,
+    pub reason: String,
+    pub seed: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    pub score: u64,
+    pub altitude: f32,
+    pub seed: u64,
+    // Секунди від початку епохи Unix; 0 означає невідому дату
+    pub date: u64,
+    pub duration_secs: f32,
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct SaveData {
+    // Файли без поля версії — це версія 0; SAVE_VERSION отримують лише нові збереження
+    #[serde(default)]
+    pub version: u32,
+    pub high_score: u64,
+    pub settings: Settings,
+    pub statistics: Statistics,
+    pub unlocks: Vec<String>,
+    pub history: Vec<RunRecord>,
+    pub leaderboard: Vec<LeaderboardEntry>,
+}
+
+impl Default for SaveData {
+    fn default() -> Self {
+        Self {
+            version: SAVE_VERSION,
+            high_score: 0,
+            settings: Settings::default(),
+            statistics: Statistics::default(),
+            unlocks: Vec::new(),
+            history: Vec::new(),
+            leaderboard: Vec::new(),
+        }
+    }
+}
+
+pub fn save_dir() -> PathBuf {
+    dirs::data_dir()
+        .map(|dir| dir.join("doodle_jump"))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+pub fn save_path() -> PathBuf {
+    save_dir().join(SAVE_FILE)
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    path.with_extension("ron.bak")
+}
+
+impl SaveData {
+    // Повертає дані та ознаку, що їх треба одразу переписати на диск
+    // (імпорт старого рекорду або відновлення після пошкодження)
+    pub fn load() -> (Self, bool) {
+        let path = save_path();
+
+        if !path.exists() {
+            return (import_legacy_high_score(), true);
+        }
+
+        match read_save(&path) {
+            Ok(data) => (data, false),
+            Err(error) => {
+                warn!("Файл збереження {:?} пошкоджено: {}", path, error);
+                quarantine(&path);
+
+                // Остання вдала копія лишається поруч із основним файлом
+                match read_save(&backup_path(&path)) {
+                    Ok(data) => {
+                        info!("Збереження відновлено з резервної копії");
+                        (data, true)
+                    }
+                    Err(_) => (SaveData::default(), true),
+                }
+            }
+        }
+    }
+
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    pub fn record_run(&mut self, record: RunRecord, bounces: u64, altitude: f32) {
+        self.high_score = self.high_score.max(record.score);
+        self.statistics.games_played = self.statistics.games_played.saturating_add(1);
+        self.statistics.total_bounces = self.statistics.total_bounces.saturating_add(bounces);
+        self.statistics.best_altitude = self.statistics.best_altitude.max(altitude);
+        let deaths = self.statistics.deaths.entry(record.reason.clone()).or_default();
+        *deaths = deaths.saturating_add(1);
+
+        self.history.push(record);
+        if self.history.len() > HISTORY_LIMIT {
+            let excess = self.history.len() - HISTORY_LIMIT;
+            self.history.drain(..excess);
+        }
+    }
+}
+
+impl SaveData {
+    pub fn qualifies_for_leaderboard(&self, score: u64) -> bool {
+        score > 0 && (self.leaderboard.len() < LEADERBOARD_SIZE
+            || self.leaderboard.last().is_some_and(|last| score > last.score))
+    }
+
+    pub fn add_leaderboard_entry(&mut self, entry: LeaderboardEntry) {
+        let position = self.leaderboard.iter()
+            .position(|existing| entry.score > existing.score)
+            .unwrap_or(self.leaderboard.len());
+        self.leaderboard.insert(position, entry);
+        self.leaderboard.truncate(LEADERBOARD_SIZE);
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn write_atomically(path: &Path, contents: &[u8], keep_backup: bool) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+    {
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+    }
+
+    if keep_backup && path.exists() {
+        fs::copy(path, backup_path(path))?;
+    }
+    fs::rename(&tmp_path, path)
+}
+
+fn read_save(path: &Path) -> Result<SaveData, String> {
+    let contents = fs::read_to_string(path).map_err(|error| error.to_string())?;
+    let data: SaveData = ron::from_str(&contents).map_err(|error| error.to_string())?;
+    migrate(data)
+}
+
+// Схема ще не змінювалася з першої версії. Коли зміниться, тут з'являться кроки
+// з версії N до N + 1, а будь-яка інша версія залишиться помилкою
+fn migrate(data: SaveData) -> Result<SaveData, String> {
+    if data.version != SAVE_VERSION {
+        return Err(format!("невідома версія збереження {}", data.version));
+    }
+    Ok(data)
+}
+
+fn quarantine(path: &Path) {
+    let stamp = unix_now();
+    let corrupt_path = path.with_extension(format!("ron.corrupt-{}", stamp));
+
+    if let Err(error) = fs::rename(path, &corrupt_path) {
+        warn!("Не вдалося перемістити пошкоджений файл {:?}: {}", path, error);
+    }
+}
+
+// Перенесення рекорду з текстового файлу попередніх версій гри, які писали туди лише число
+fn import_legacy_high_score() -> SaveData {
+    let mut data = SaveData::default();
+
+    if let Ok(content) = fs::read_to_string(LEGACY_HIGH_SCORE_FILE) {
+        data.high_score = content.trim().parse::<u64>().unwrap_or(0);
+    }
+
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(contents: &str) -> Result<SaveData, String> {
+        let data: SaveData = ron::from_str(contents).map_err(|error| error.to_string())?;
+        migrate(data)
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut data = SaveData::default();
+        data.record_run(RunRecord { score: 120, reason: "Fell".to_string(), seed: 7 }, 15, 900.0);
+        data.add_leaderboard_entry(LeaderboardEntry { name: "Оля".to_string(), score: 120, ..default() });
+
+        let restored = parse(&data.to_ron().unwrap()).unwrap();
+
+        assert_eq!(restored.version, SAVE_VERSION);
+        assert_eq!(restored.high_score, 120);
+        assert_eq!(restored.history.len(), 1);
+        assert_eq!(restored.leaderboard[0].name, "Оля");
+        assert_eq!(restored.statistics.total_bounces, 15);
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        assert!(parse(&format!("(version: {})", SAVE_VERSION + 1)).is_err());
+        assert!(parse("(version: 0)").is_err());
+    }
+
+    #[test]
+    fn missing_version_is_version_zero() {
+        let data: SaveData = ron::from_str("(high_score: 5)").unwrap();
+
+        assert_eq!(data.version, 0);
+        assert!(migrate(data).is_err());
+    }
+
+    #[test]
+    fn missing_fields_take_defaults() {
+        let data = parse(&format!("(version: {}, high_score: 5)", SAVE_VERSION)).unwrap();
+
+        assert_eq!(data.high_score, 5);
+        assert_eq!(data.settings.skin, DEFAULT_SKIN);
+        assert!(data.leaderboard.is_empty());
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(parse("not a save").is_err());
+    }
+}
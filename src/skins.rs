@@ -0,0 +1,295 @@
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+use serde::Deserialize;
+use crate::game::GameState;
+use crate::persistence::SaveRequested;
+use crate::player::{PLAYER_BODY_HALF_WIDTH, PLAYER_HALF_HEIGHT};
+use crate::replay::ReplayPlayback;
+use crate::save::SaveData;
+
+pub const SKIN_MANIFEST: &str = "skins.ron";
+pub const DEFAULT_SKIN: &str = "player1";
+pub const DEFAULT_JUMP_SOUND: &str = "music/jump.mp3";
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct SkinDef {
+    pub id: String,
+    pub name: String,
+    pub sprite: String,
+    pub half_size: (f32, f32),
+    #[serde(default)]
+    pub faces_left: bool,
+    #[serde(default)]
+    pub jump_sound: Option<String>,
+    #[serde(default)]
+    pub atlas: Option<SkinAtlas>,
+    // Розкладка атласу будується один раз під час завантаження реєстру
+    #[serde(skip)]
+    pub atlas_layout: Option<Handle<TextureAtlasLayout>>,
+}
+
+// Необов'язковий атлас кадрів: спрайт розрізається на сітку, а кадри обираються за станом анімації
+#[derive(Deserialize, Clone, Debug)]
+pub struct SkinAtlas {
+    pub tile_size: (u32, u32),
+    pub columns: u32,
+    pub rows: u32,
+    pub fall: usize,
+    pub launch: usize,
+    pub shoot: usize,
+}
+
+impl SkinDef {
+    fn builtin() -> Self {
+        Self {
+            id: DEFAULT_SKIN.to_string(),
+            name: "Дудл".to_string(),
+            sprite: "images/players/player1.png".to_string(),
+            half_size: (PLAYER_BODY_HALF_WIDTH, PLAYER_HALF_HEIGHT),
+            faces_left: false,
+            jump_sound: None,
+            atlas: None,
+            atlas_layout: None,
+        }
+    }
+
+    fn build_atlas_layout(&mut self, layouts: &mut Assets<TextureAtlasLayout>) {
+        let Some(atlas) = &self.atlas else { return };
+        let layout = TextureAtlasLayout::from_grid(
+            UVec2::new(atlas.tile_size.0, atlas.tile_size.1),
+            atlas.columns,
+            atlas.rows,
+            None,
+            None,
+        );
+        self.atlas_layout = Some(layouts.add(layout));
+    }
+
+    pub fn texture_atlas(&self) -> Option<TextureAtlas> {
+        let (atlas, layout) = (self.atlas.as_ref()?, self.atlas_layout.clone()?);
+        Some(TextureAtlas { layout, index: atlas.fall })
+    }
+
+    pub fn sprite(&self, asset_server: &AssetServer) -> Sprite {
+        Sprite {
+            image: asset_server.load(&self.sprite),
+            texture_atlas: self.texture_atlas(),
+            ..default()
+        }
+    }
+}
+
+// Персонажі з маніфесту assets/skins.ron
+#[derive(Resource)]
+pub struct SkinRegistry {
+    pub skins: Vec<SkinDef>,
+}
+
+impl SkinRegistry {
+    pub fn load(layouts: &mut Assets<TextureAtlasLayout>) -> Self {
+        let path = FileAssetReader::get_base_path().join("assets").join(SKIN_MANIFEST);
+        let mut skins = match std::fs::read_to_string(&path) {
+            Ok(content) => ron::from_str::<Vec<SkinDef>>(&content).unwrap_or_else(|error| {
+                warn!("Не вдалося прочитати маніфест персонажів {:?}: {}", path, error);
+                Vec::new()
+            }),
+            Err(error) => {
+                warn!("Не вдалося відкрити маніфест персонажів {:?}: {}", path, error);
+                Vec::new()
+            }
+        };
+
+        if skins.is_empty() {
+            skins.push(SkinDef::builtin());
+        }
+        for skin in skins.iter_mut() {
+            skin.build_atlas_layout(layouts);
+        }
+        Self { skins }
+    }
+
+    pub fn index_of(&self, id: &str) -> Option<usize> {
+        self.skins.iter().position(|skin| skin.id == id)
+    }
+
+    // Невідомий персонаж (наприклад, видалений з маніфесту) замінюється першим
+    pub fn get(&self, id: &str) -> &SkinDef {
+        self.index_of(id).map_or(&self.skins[0], |index| &self.skins[index])
+    }
+}
+
+// Тіло та звуки персонажа, з яким гравець вийшов на рівень
+#[derive(Component, Clone)]
+pub struct PlayerSkin {
+    pub faces_left: bool,
+    pub jump_sound: Handle<AudioSource>,
+    pub atlas: Option<SkinAtlas>,
+}
+
+impl PlayerSkin {
+    pub fn new(def: &SkinDef, asset_server: &AssetServer) -> Self {
+        Self {
+            faces_left: def.faces_left,
+            jump_sound: asset_server.load(def.jump_sound.as_deref().unwrap_or(DEFAULT_JUMP_SOUND)),
+            atlas: def.atlas.clone(),
+        }
+    }
+}
+
+// Повтор завжди грається тим персонажем, яким його записали
+pub fn active_skin_id<'a>(save: &'a SaveData, playback: &'a ReplayPlayback) -> &'a str {
+    match &playback.replay {
+        Some(replay) => &replay.skin,
+        None => &save.settings.skin,
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct SkinSelection(pub usize);
+
+#[derive(Component)]
+pub struct SkinSelectScreen;
+
+pub struct SkinsPlugin;
+
+impl Plugin for SkinsPlugin {
+    fn build(&self, app: &mut App) {
+        let registry = SkinRegistry::load(&mut app.world_mut().resource_mut::<Assets<TextureAtlasLayout>>());
+
+        app.insert_resource(registry)
+            .init_resource::<SkinSelection>()
+            .add_systems(OnEnter(GameState::SkinSelect), open_skin_select)
+            .add_systems(OnExit(GameState::SkinSelect), clean_skin_select)
+            .add_systems(Update, toggle_skin_select)
+            .add_systems(Update, (skin_select_input, show_skin_select)
+                .chain()
+                .run_if(in_state(GameState::SkinSelect)));
+    }
+}
+
+fn toggle_skin_select(
+    mut next_state: ResMut<NextState<GameState>>,
+    key_code: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+) {
+    match state.get() {
+        GameState::NotStarted if key_code.just_pressed(KeyCode::KeyC) => {
+            next_state.set(GameState::SkinSelect);
+        }
+        GameState::SkinSelect if key_code.just_pressed(KeyCode::Escape) => {
+            next_state.set(GameState::NotStarted);
+        }
+        _ => {}
+    }
+}
+
+fn open_skin_select(
+    registry: Res<SkinRegistry>,
+    save: Res<SaveData>,
+    mut selection: ResMut<SkinSelection>,
+) {
+    selection.0 = registry.index_of(&save.settings.skin).unwrap_or(0);
+}
+
+fn skin_select_input(
+    key_code: Res<ButtonInput<KeyCode>>,
+    registry: Res<SkinRegistry>,
+    mut selection: ResMut<SkinSelection>,
+    mut save: ResMut<SaveData>,
+    mut save_requests: EventWriter<SaveRequested>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let len = registry.skins.len();
+
+    if key_code.just_pressed(KeyCode::ArrowRight) || key_code.just_pressed(KeyCode::KeyD) {
+        selection.0 = (selection.0 + 1) % len;
+    }
+    if key_code.just_pressed(KeyCode::ArrowLeft) || key_code.just_pressed(KeyCode::KeyA) {
+        selection.0 = (selection.0 + len - 1) % len;
+    }
+
+    if key_code.just_pressed(KeyCode::Enter) {
+        let id = registry.skins[selection.0].id.clone();
+        if save.settings.skin != id {
+            save.settings.skin = id;
+            save_requests.send(SaveRequested);
+        }
+        next_state.set(GameState::NotStarted);
+    }
+}
+
+fn show_skin_select(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    registry: Res<SkinRegistry>,
+    selection: Res<SkinSelection>,
+    screen_q: Query<Entity, With<SkinSelectScreen>>,
+) {
+    if !selection.is_changed() && !screen_q.is_empty() {
+        return;
+    }
+    for entity in screen_q.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let skin = &registry.skins[selection.0];
+    let image = asset_server.load(&skin.sprite);
+    let preview = match skin.texture_atlas() {
+        Some(atlas) => ImageNode::from_atlas_image(image, atlas),
+        None => ImageNode::new(image),
+    };
+
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(20.0),
+            ..default()
+        },
+        SkinSelectScreen,
+    )).with_children(|parent| {
+        parent.spawn((
+            Text::new("Вибір персонажа"),
+            TextFont {
+                font: asset_server.load("fonts/Arsenal-Bold.ttf"),
+                font_size: 40.0,
+                ..default()
+            },
+            TextColor(Color::BLACK),
+        ));
+
+        // Без явного розміру вузол бере розмір спрайта або кадру атласу;
+        // half_size описує лише тіло для зіткнень, без носа
+        parent.spawn(preview);
+
+        parent.spawn((
+            Text::new(format!("{}  ({}/{})", skin.name, selection.0 + 1, registry.skins.len())),
+            TextFont {
+                font: asset_server.load("fonts/Arsenal-Regular.ttf"),
+                font_size: 30.0,
+                ..default()
+            },
+            TextColor(Color::BLACK),
+        ));
+
+        parent.spawn((
+            Text::new("←/→ — вибір, Enter — підтвердити, Esc — назад"),
+            TextFont {
+                font: asset_server.load("fonts/Arsenal-Italic.ttf"),
+                font_size: 22.0,
+                ..default()
+            },
+            TextColor(Color::BLACK),
+        ));
+    });
+}
+
+fn clean_skin_select(mut commands: Commands, screen_q: Query<Entity, With<SkinSelectScreen>>) {
+    for entity in screen_q.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}